@@ -0,0 +1,18 @@
+use std::io::{Read, Write};
+
+use prost::Message;
+use prutoipa_build::generate_plugin_response;
+
+/// `protoc` plugin reading a `CodeGeneratorRequest` from stdin and writing the
+/// `CodeGeneratorResponse` to stdout, the warnings go to stderr.
+fn main() -> std::io::Result<()> {
+    let mut request_encoded = Vec::new();
+    std::io::stdin().read_to_end(&mut request_encoded)?;
+
+    let (response, warnings) = generate_plugin_response(&request_encoded);
+    for warning in warnings {
+        eprintln!("warning: {warning}");
+    }
+
+    std::io::stdout().write_all(&response.encode_to_vec())
+}
@@ -0,0 +1,400 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use clap::{Parser, ValueEnum};
+use prutoipa_build::{
+    Builder, DocumentFormat, EnumRepresentation, Generated, JsonMapping, OneOfStyle,
+    OpenApiVersion, PresencePolicy, PropertyNaming, PropertyPresence, PrutoipaBuildError,
+};
+
+/// Contents of the outputs, by path
+type Files = Vec<(PathBuf, Vec<u8>)>;
+
+/// Generate utoipa implementations or OpenAPI documents from encoded `FileDescriptorSet` files.
+#[derive(Debug, Parser)]
+#[command(name = "prutoipa", version)]
+struct Args {
+    /// Encoded `FileDescriptorSet` files, like the ones written by `protoc --descriptor_set_out`
+    #[arg(required = true)]
+    descriptor_sets: Vec<PathBuf>,
+
+    /// Directory where the outputs are written
+    #[arg(short, long)]
+    out_dir: PathBuf,
+
+    /// Outputs to generate, can be repeated
+    #[arg(
+        short = 'f',
+        long = "format",
+        value_name = "FORMAT",
+        value_enum,
+        default_values_t = [Output::Rust]
+    )]
+    outputs: Vec<Output>,
+
+    /// Exit with an error when the outputs at the output directory are missing or stale, instead
+    /// of writing them
+    #[arg(long)]
+    check: bool,
+
+    /// Fail with the first warning instead of reporting the warnings
+    #[arg(long)]
+    strict: bool,
+
+    /// Warn about every method without a google.api.http annotation
+    #[arg(long)]
+    warn_unannotated_methods: bool,
+
+    /// Generate utoipa enum_values property with the value numbers at enums
+    #[arg(long)]
+    enum_values: bool,
+
+    /// How enums are represented
+    #[arg(long, value_enum, default_value_t = EnumRepresentationArg::String)]
+    enum_representation: EnumRepresentationArg,
+
+    /// How one-of groups are represented
+    #[arg(long, value_enum, default_value_t = OneOfStyleArg::Flattened)]
+    oneof_style: OneOfStyleArg,
+
+    /// How 64-bit integers are represented
+    #[arg(long, value_enum, default_value_t = JsonMappingArg::Canonical)]
+    json_mapping: JsonMappingArg,
+
+    /// How property names are derived from field names
+    #[arg(long, value_enum, default_value_t = PropertyNamingArg::JsonName)]
+    property_naming: PropertyNamingArg,
+
+    /// Which properties are required or nullable
+    #[arg(long, value_enum, default_value_t = PresencePolicyArg::Strict)]
+    presence_policy: PresencePolicyArg,
+
+    /// Presence of the property of a field, like `package.Message.field=nullable`, can be
+    /// repeated
+    #[arg(long, value_name = "FIELD=PRESENCE", value_parser = parse_field_presence)]
+    field_presence: Vec<(String, PropertyPresence)>,
+
+    /// Document the zero value of proto3 fields without explicit presence as their default
+    #[arg(long)]
+    implicit_defaults: bool,
+
+    /// Keep the Markdown of proto comments at descriptions
+    #[arg(long)]
+    markdown_comments: bool,
+
+    /// Generate an `openapi` function at every package, and title the OpenAPI documents
+    #[arg(long, num_args = 2, value_names = ["TITLE", "VERSION"])]
+    openapi: Option<Vec<String>>,
+
+    /// OpenAPI version of the OpenAPI documents
+    #[arg(long, value_enum, default_value_t = OpenApiVersionArg::V3_0)]
+    openapi_version: OpenApiVersionArg,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum Output {
+    /// `<package>.utoipa.rs` files with the utoipa implementations
+    Rust,
+    /// `<package>.openapi.json` files with the OpenAPI documents
+    Json,
+    /// `<package>.openapi.yaml` files with the OpenAPI documents
+    Yaml,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum EnumRepresentationArg {
+    String,
+    Integer,
+    Either,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum OneOfStyleArg {
+    Flattened,
+    Nested,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum JsonMappingArg {
+    Canonical,
+    Lenient,
+    Numeric,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum PropertyNamingArg {
+    ProtoName,
+    JsonName,
+    SnakeCase,
+    CamelCase,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum PresencePolicyArg {
+    Strict,
+    WireAccurate,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum OpenApiVersionArg {
+    #[value(name = "3.0")]
+    V3_0,
+    #[value(name = "3.1")]
+    V3_1,
+}
+
+impl Output {
+    fn get_file_name(&self, package_name: &str) -> String {
+        match self {
+            Self::Rust => format!("{package_name}.utoipa.rs"),
+            Self::Json => format!(
+                "{package_name}.openapi.{}",
+                DocumentFormat::Json.get_extension()
+            ),
+            Self::Yaml => format!(
+                "{package_name}.openapi.{}",
+                DocumentFormat::Yaml.get_extension()
+            ),
+        }
+    }
+}
+
+impl Args {
+    fn get_builder(&self) -> Result<Builder, PrutoipaBuildError> {
+        let mut builder = Builder::new();
+
+        for descriptor_set in &self.descriptor_sets {
+            let fds_encoded = fs::read(descriptor_set).map_err(|err| {
+                std::io::Error::new(err.kind(), format!("{}: {err}", descriptor_set.display()))
+            })?;
+            builder.register_descriptors_encoded(&fds_encoded)?;
+        }
+
+        if self.strict {
+            builder.strict();
+        }
+        if self.warn_unannotated_methods {
+            builder.warn_unannotated_methods();
+        }
+        if self.enum_values {
+            builder.generate_enum_values();
+        }
+        if self.implicit_defaults {
+            builder.document_implicit_defaults();
+        }
+        if self.markdown_comments {
+            builder.markdown_comments();
+        }
+        if let Some([title, version]) = self.openapi.as_deref() {
+            builder.openapi(title, version);
+        }
+        for (field, presence) in &self.field_presence {
+            builder.field_presence(field, *presence);
+        }
+
+        builder
+            .enum_representation(match self.enum_representation {
+                EnumRepresentationArg::String => EnumRepresentation::String,
+                EnumRepresentationArg::Integer => EnumRepresentation::Integer,
+                EnumRepresentationArg::Either => EnumRepresentation::Either,
+            })
+            .oneof_style(match self.oneof_style {
+                OneOfStyleArg::Flattened => OneOfStyle::Flattened,
+                OneOfStyleArg::Nested => OneOfStyle::Nested,
+            })
+            .json_mapping(match self.json_mapping {
+                JsonMappingArg::Canonical => JsonMapping::Canonical,
+                JsonMappingArg::Lenient => JsonMapping::Lenient,
+                JsonMappingArg::Numeric => JsonMapping::Numeric,
+            })
+            .property_naming(match self.property_naming {
+                PropertyNamingArg::ProtoName => PropertyNaming::ProtoName,
+                PropertyNamingArg::JsonName => PropertyNaming::JsonName,
+                PropertyNamingArg::SnakeCase => PropertyNaming::SnakeCase,
+                PropertyNamingArg::CamelCase => PropertyNaming::CamelCase,
+            })
+            .presence_policy(match self.presence_policy {
+                PresencePolicyArg::Strict => PresencePolicy::Strict,
+                PresencePolicyArg::WireAccurate => PresencePolicy::WireAccurate,
+            })
+            .openapi_version(match self.openapi_version {
+                OpenApiVersionArg::V3_0 => OpenApiVersion::V3_0,
+                OpenApiVersionArg::V3_1 => OpenApiVersion::V3_1,
+            });
+
+        Ok(builder)
+    }
+}
+
+fn parse_field_presence(value: &str) -> Result<(String, PropertyPresence), String> {
+    let (field, presence) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected FIELD=PRESENCE, found '{value}'"))?;
+
+    match presence {
+        "required" => Ok((field.to_string(), PropertyPresence::Required)),
+        "optional" => Ok((field.to_string(), PropertyPresence::Optional)),
+        "nullable" => Ok((field.to_string(), PropertyPresence::Nullable)),
+        _ => Err(format!(
+            "expected required, optional or nullable presence, found '{presence}'"
+        )),
+    }
+}
+
+/// Returns the generated outputs by path, with the warnings about the descriptors.
+fn generate(
+    builder: &Builder,
+    outputs: &[Output],
+    out_dir: &Path,
+) -> Result<(Files, Vec<String>), PrutoipaBuildError> {
+    let mut files = Vec::new();
+    let mut warnings = Vec::new();
+
+    for output in outputs {
+        let write_factory = |_| Ok(Vec::new());
+        let generated: Generated<Vec<u8>> = match output {
+            Output::Rust => builder.generate(write_factory)?,
+            Output::Json => builder.generate_documents(DocumentFormat::Json, write_factory)?,
+            Output::Yaml => builder.generate_documents(DocumentFormat::Yaml, write_factory)?,
+        };
+
+        // Every output is generated from the same descriptors, so they share the warnings
+        warnings = generated
+            .warnings
+            .iter()
+            .map(|warning| warning.to_string())
+            .collect();
+        files.extend(generated.files.into_iter().map(|(package_name, content)| {
+            (out_dir.join(output.get_file_name(&package_name)), content)
+        }));
+    }
+
+    Ok((files, warnings))
+}
+
+/// Returns the paths of the files missing at the file system or differing from their content.
+fn get_stale_files(files: &Files) -> Vec<PathBuf> {
+    files
+        .iter()
+        .filter(|(path, content)| fs::read(path).ok().as_ref() != Some(content))
+        .map(|(path, _)| path.clone())
+        .collect()
+}
+
+fn run(args: &Args) -> Result<bool, PrutoipaBuildError> {
+    let builder = args.get_builder()?;
+
+    let mut outputs = args.outputs.clone();
+    outputs.sort();
+    outputs.dedup();
+
+    let (files, warnings) = generate(&builder, &outputs, &args.out_dir)?;
+    for warning in warnings {
+        eprintln!("warning: {warning}");
+    }
+
+    if args.check {
+        let stale_files = get_stale_files(&files);
+        for path in &stale_files {
+            eprintln!("error: {} is not up to date", path.display());
+        }
+
+        return Ok(stale_files.is_empty());
+    }
+
+    fs::create_dir_all(&args.out_dir)?;
+    for (path, content) in files {
+        fs::write(path, content)?;
+    }
+
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_presence() {
+        assert_eq!(
+            parse_field_presence("people.Person.parent=nullable"),
+            Ok((
+                "people.Person.parent".to_string(),
+                PropertyPresence::Nullable
+            ))
+        );
+        assert!(parse_field_presence("people.Person.parent").is_err());
+        assert!(parse_field_presence("people.Person.parent=absent").is_err());
+    }
+
+    #[test]
+    fn args() {
+        let args = Args::try_parse_from([
+            "prutoipa",
+            "people.bin",
+            "--out-dir",
+            "out",
+            "--format",
+            "json",
+            "--format",
+            "yaml",
+            "--openapi",
+            "People API",
+            "1.0.0",
+            "--openapi-version",
+            "3.1",
+            "--check",
+        ])
+        .unwrap();
+
+        assert_eq!(args.descriptor_sets, vec![PathBuf::from("people.bin")]);
+        assert_eq!(args.outputs, vec![Output::Json, Output::Yaml]);
+        assert_eq!(
+            args.openapi,
+            Some(vec!["People API".to_string(), "1.0.0".to_string()])
+        );
+        assert!(matches!(args.openapi_version, OpenApiVersionArg::V3_1));
+        assert!(args.check);
+
+        assert!(Args::try_parse_from(["prutoipa", "--out-dir", "out"]).is_err());
+    }
+
+    #[test]
+    fn stale_files() {
+        let out_dir = std::env::temp_dir().join(format!("prutoipa-{}", std::process::id()));
+        fs::create_dir_all(&out_dir).unwrap();
+        fs::write(out_dir.join("fresh.utoipa.rs"), "fresh").unwrap();
+        fs::write(out_dir.join("stale.utoipa.rs"), "stale").unwrap();
+
+        let stale_files = get_stale_files(&vec![
+            (out_dir.join("fresh.utoipa.rs"), b"fresh".to_vec()),
+            (out_dir.join("stale.utoipa.rs"), b"fresh".to_vec()),
+            (out_dir.join("missing.utoipa.rs"), b"fresh".to_vec()),
+        ]);
+        fs::remove_dir_all(&out_dir).unwrap();
+
+        assert_eq!(
+            stale_files,
+            vec![
+                out_dir.join("stale.utoipa.rs"),
+                out_dir.join("missing.utoipa.rs")
+            ]
+        );
+    }
+}
@@ -0,0 +1,114 @@
+pub mod source_code_info;
+
+use std::path::Path;
+
+use prost_types::FileDescriptorProto;
+use protobuf::Message as _;
+
+use crate::{compile::source_code_info::get_source_code_info, error::PrutoipaBuildError};
+
+/// Field number of the files at `google.protobuf.FileDescriptorSet`.
+const FILE_DESCRIPTOR_SET_FILE: u32 = 1;
+/// Field number of `source_code_info` at `google.protobuf.FileDescriptorProto`.
+const FILE_SOURCE_CODE_INFO: u32 = 9;
+
+/// Compiles `.proto` files and their imports with a pure-Rust parser, returning them as an encoded
+/// `FileDescriptorSet`. Files are encoded as parsed, so the `google.api.http` annotations of their
+/// methods are kept, followed by the `SourceCodeInfo` of their sources.
+///
+/// Decoded, the set also builds the prost structs with `prost_build::Config::compile_fds`.
+pub fn compile_protos<P: AsRef<Path>>(
+    protos: &[P],
+    includes: &[P],
+) -> Result<Vec<u8>, PrutoipaBuildError> {
+    let parsed = protobuf_parse::Parser::new()
+        .pure()
+        .includes(includes)
+        .inputs(protos)
+        .parse_and_typecheck()
+        .map_err(|err| PrutoipaBuildError::InvalidProtoFiles(format!("{err:#}")))?;
+
+    let mut fds_encoded = Vec::new();
+    for file in parsed.file_descriptors {
+        let mut file_encoded = file
+            .write_to_bytes()
+            .map_err(|err| PrutoipaBuildError::InvalidProtoFiles(err.to_string()))?;
+
+        // Files bundled with the parser, like the well-known types, have no source at the includes
+        let source_path = includes
+            .iter()
+            .map(|include| include.as_ref().join(file.name()))
+            .find(|source_path| source_path.is_file());
+        if let Some(source_path) = source_path {
+            let source = std::fs::read_to_string(source_path)?;
+            let file_descriptor_proto: FileDescriptorProto =
+                prost::Message::decode(file_encoded.as_slice())?;
+
+            prost::encoding::message::encode(
+                FILE_SOURCE_CODE_INFO,
+                &get_source_code_info(&source, &file_descriptor_proto),
+                &mut file_encoded,
+            );
+        }
+
+        prost::encoding::bytes::encode(FILE_DESCRIPTOR_SET_FILE, &file_encoded, &mut fds_encoded);
+    }
+
+    Ok(fds_encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Builder;
+
+    #[test]
+    fn compile_protos_with_comments() {
+        let include = std::env::temp_dir().join(format!("prutoipa-compile-{}", std::process::id()));
+        std::fs::create_dir_all(include.join("people")).unwrap();
+        std::fs::write(
+            include.join("people/person.proto"),
+            [
+                "syntax = \"proto3\";",
+                "",
+                "package people;",
+                "",
+                "import \"google/protobuf/timestamp.proto\";",
+                "",
+                "// A person.",
+                "message Person {",
+                "  int32 id = 1; // Its id.",
+                "  google.protobuf.Timestamp born_at = 2;",
+                "}",
+                "",
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let mut builder = Builder::new();
+        builder
+            .compile_protos(
+                &[include.join("people/person.proto")],
+                std::slice::from_ref(&include),
+            )
+            .unwrap();
+        let generated = builder.generate(|_| Ok(Vec::<u8>::new())).unwrap();
+        std::fs::remove_dir_all(&include).unwrap();
+
+        let code = String::from_utf8(generated.files[0].1.clone()).unwrap();
+
+        assert_eq!(generated.files.len(), 1);
+        assert_eq!(generated.files[0].0, "people");
+        assert!(code.contains(".description(Some(\"A person.\"))"));
+        assert!(code.contains(".description(Some(\"Its id.\"))"));
+        assert!(code.contains("utoipa::openapi::KnownFormat::DateTime"));
+    }
+
+    #[test]
+    fn compile_protos_error() {
+        let err = compile_protos(&["missing.proto"], &["."]).err().unwrap();
+
+        assert!(matches!(err, PrutoipaBuildError::InvalidProtoFiles(_)));
+    }
+}
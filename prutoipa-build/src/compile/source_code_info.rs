@@ -0,0 +1,1104 @@
+//! `SourceCodeInfo` of `.proto` sources. The pure-Rust parser drops the comments, so sources are
+//! scanned again to attach them to their declarations the way `protoc` does.
+
+use prost_types::{
+    source_code_info::Location, DescriptorProto, EnumDescriptorProto, FieldDescriptorProto,
+    FileDescriptorProto, ServiceDescriptorProto, SourceCodeInfo,
+};
+
+use crate::descriptor::comments::{
+    get_child_path, ENUM_VALUE, FILE_ENUM_TYPE, FILE_EXTENSION, FILE_MESSAGE_TYPE, FILE_PACKAGE,
+    FILE_SERVICE, MESSAGE_ENUM_TYPE, MESSAGE_EXTENSION, MESSAGE_FIELD, MESSAGE_NESTED_TYPE,
+    MESSAGE_ONEOF_DECL, SERVICE_METHOD,
+};
+
+/// Token of a `.proto` source with the comments attached to it.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Token {
+    text: String,
+    /// Zero-based line and column the token starts at
+    start: (i32, i32),
+    /// Zero-based line and column the token ends at, exclusive
+    end: (i32, i32),
+    leading_comments: Option<String>,
+    trailing_comments: Option<String>,
+}
+
+/// Splits a source in tokens, dropping whitespaces and comments.
+struct Tokenizer {
+    chars: Vec<char>,
+    idx: usize,
+    line: i32,
+    column: i32,
+}
+
+/// Comments found between two tokens, `protoc` `CommentCollector`.
+#[derive(Debug, Default)]
+struct CommentCollector {
+    buffer: Option<String>,
+    is_line_comment: bool,
+    can_attach_to_prev: bool,
+    trailing_comments: Option<String>,
+}
+
+/// Fields declared at the same scope, the ones of a message or the extensions of its `extend`
+/// blocks, along with the messages their groups are declared at.
+#[derive(Clone, Copy)]
+struct FieldScope<'d> {
+    path: &'d [i32],
+    fields: &'d [FieldDescriptorProto],
+    field_number: i32,
+    nested_types: &'d [DescriptorProto],
+    nested_type_number: i32,
+}
+
+/// Builds the locations of the declarations of a source, looking their descriptors up by name.
+struct Parser<'a> {
+    tokens: &'a [Token],
+    idx: usize,
+    locations: Vec<Location>,
+}
+
+/// Returns the `SourceCodeInfo` of the source of a file, with the spans and the leading and
+/// trailing comments of its declarations.
+pub fn get_source_code_info(source: &str, file: &FileDescriptorProto) -> SourceCodeInfo {
+    let tokens = Tokenizer::new(source).get_tokens();
+    let mut parser = Parser {
+        tokens: &tokens,
+        idx: 0,
+        locations: Vec::new(),
+    };
+    parser.parse_file(file);
+
+    SourceCodeInfo {
+        location: parser.locations,
+    }
+}
+
+impl Tokenizer {
+    fn new(source: &str) -> Self {
+        Self {
+            chars: source.chars().collect(),
+            idx: 0,
+            line: 0,
+            column: 0,
+        }
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.idx + offset).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let char = self.peek(0)?;
+        self.idx += 1;
+        if char == '\n' {
+            self.line += 1;
+            self.column = 0;
+        } else {
+            self.column += 1;
+        }
+
+        Some(char)
+    }
+
+    fn consume_whitespaces(&mut self) {
+        while self
+            .peek(0)
+            .is_some_and(|char| char.is_whitespace() && char != '\n')
+        {
+            self.bump();
+        }
+    }
+
+    fn try_consume_newline(&mut self) -> bool {
+        match self.peek(0) {
+            Some('\n') => self.bump().is_some(),
+            _ => false,
+        }
+    }
+
+    /// Consumes a line comment, returning its text after `//` with the newline.
+    fn try_consume_line_comment(&mut self) -> Option<String> {
+        if (self.peek(0), self.peek(1)) != (Some('/'), Some('/')) {
+            return None;
+        }
+        self.bump();
+        self.bump();
+
+        let mut comment = String::new();
+        while let Some(char) = self.bump() {
+            comment.push(char);
+            if char == '\n' {
+                break;
+            }
+        }
+
+        Some(comment)
+    }
+
+    /// Consumes a block comment, returning its text between `/*` and `*/` without the leading
+    /// `*` of its lines.
+    fn try_consume_block_comment(&mut self) -> Option<String> {
+        if (self.peek(0), self.peek(1)) != (Some('/'), Some('*')) {
+            return None;
+        }
+        self.bump();
+        self.bump();
+
+        let mut comment = String::new();
+        while let Some(char) = self.peek(0) {
+            if (char, self.peek(1)) == ('*', Some('/')) {
+                self.bump();
+                self.bump();
+                break;
+            }
+
+            comment.push(char);
+            self.bump();
+            if char == '\n' {
+                self.consume_whitespaces();
+                if (self.peek(0), self.peek(1)) == (Some('*'), Some('/')) {
+                    continue;
+                }
+                if self.peek(0) == Some('*') {
+                    self.bump();
+                }
+            }
+        }
+
+        Some(comment)
+    }
+
+    fn read_token(&mut self) -> Option<Token> {
+        let start = (self.line, self.column);
+        let first = self.bump()?;
+
+        let mut text = first.to_string();
+        if first.is_alphanumeric() || first == '_' || first == '.' {
+            while self
+                .peek(0)
+                .is_some_and(|char| char.is_alphanumeric() || char == '_' || char == '.')
+            {
+                text.extend(self.bump());
+            }
+        } else if first == '"' || first == '\'' {
+            while let Some(char) = self.bump() {
+                text.push(char);
+                if char == '\\' {
+                    text.extend(self.bump());
+                } else if char == first || char == '\n' {
+                    break;
+                }
+            }
+        }
+
+        Some(Token {
+            text,
+            start,
+            end: (self.line, self.column),
+            ..Default::default()
+        })
+    }
+
+    /// Returns the tokens of the source, following `protoc` `Tokenizer::NextWithComments` to
+    /// attach the comments to them.
+    fn get_tokens(mut self) -> Vec<Token> {
+        let mut tokens = Vec::<Token>::new();
+
+        loop {
+            let mut collector = CommentCollector {
+                can_attach_to_prev: !tokens.is_empty(),
+                ..Default::default()
+            };
+
+            // A comment at the line of the previous token is attached to it
+            let mut next_at_line = false;
+            if !tokens.is_empty() {
+                self.consume_whitespaces();
+                if let Some(comment) = self.try_consume_line_comment() {
+                    collector.add_line_comment(comment);
+                    collector.flush();
+                } else if let Some(comment) = self.try_consume_block_comment() {
+                    collector.add_block_comment(comment);
+                    self.consume_whitespaces();
+                    if self.try_consume_newline() {
+                        collector.flush();
+                    } else {
+                        // The next token is at the same line, the comment belongs to neither
+                        collector.buffer = None;
+                        next_at_line = true;
+                    }
+                } else if !self.try_consume_newline() {
+                    next_at_line = true;
+                }
+            }
+
+            let mut token = None;
+            while token.is_none() {
+                self.consume_whitespaces();
+                if !next_at_line {
+                    if let Some(comment) = self.try_consume_line_comment() {
+                        collector.add_line_comment(comment);
+                        continue;
+                    }
+                    if let Some(comment) = self.try_consume_block_comment() {
+                        collector.add_block_comment(comment);
+                        self.consume_whitespaces();
+                        self.try_consume_newline();
+                        continue;
+                    }
+                    if self.try_consume_newline() {
+                        collector.flush();
+                        collector.can_attach_to_prev = false;
+                        continue;
+                    }
+                }
+                next_at_line = false;
+
+                match self.read_token() {
+                    Some(next_token) => token = Some(next_token),
+                    None => break,
+                }
+            }
+
+            // Comments at the end of a scope can not be attached to the following token
+            let at_scope_end = match &token {
+                Some(token) => matches!(token.text.as_str(), "}" | "]" | ")"),
+                None => true,
+            };
+            if at_scope_end {
+                collector.flush();
+            }
+            if let Some(previous_token) = tokens.last_mut() {
+                previous_token.trailing_comments = collector.trailing_comments.take();
+            }
+
+            match token {
+                Some(token) => tokens.push(Token {
+                    leading_comments: collector.buffer,
+                    ..token
+                }),
+                None => return tokens,
+            }
+        }
+    }
+}
+
+impl CommentCollector {
+    fn add_line_comment(&mut self, comment: String) {
+        if self.buffer.is_some() && !self.is_line_comment {
+            self.flush();
+        }
+
+        self.is_line_comment = true;
+        self.buffer
+            .get_or_insert_with(String::new)
+            .push_str(&comment);
+    }
+
+    fn add_block_comment(&mut self, comment: String) {
+        self.flush();
+
+        self.is_line_comment = false;
+        self.buffer = Some(comment);
+    }
+
+    /// Attaches the buffered comment to the previous token if it still can be, detached
+    /// comments are dropped.
+    fn flush(&mut self) {
+        if let Some(comment) = self.buffer.take() {
+            if self.can_attach_to_prev {
+                self.trailing_comments = Some(comment);
+                self.can_attach_to_prev = false;
+            }
+        }
+    }
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self, offset: usize) -> Option<&str> {
+        self.tokens
+            .get(self.idx + offset)
+            .map(|token| token.text.as_str())
+    }
+
+    /// Consumes a token, returning its index.
+    fn bump(&mut self) -> usize {
+        self.idx += 1;
+        self.idx - 1
+    }
+
+    /// Whether the next tokens start a `keyword Name {` block.
+    fn is_block(&self, keyword: &str) -> bool {
+        self.peek(0) == Some(keyword) && self.peek(2) == Some("{")
+    }
+
+    /// Consumes tokens up to the closing one of the next `{`, `[` or `(` group, returning its
+    /// index.
+    fn skip_group(&mut self) -> usize {
+        let mut depth = 0;
+        while let Some(text) = self.peek(0) {
+            match text {
+                "{" | "[" | "(" => depth += 1,
+                "}" | "]" | ")" => depth -= 1,
+                _ => {}
+            }
+
+            let idx = self.bump();
+            if depth <= 0 {
+                return idx;
+            }
+        }
+
+        self.idx.saturating_sub(1)
+    }
+
+    /// Consumes tokens up to the end of the statement, a `;` or a closing `}` outside groups,
+    /// returning the index of the last one.
+    fn skip_statement(&mut self) -> usize {
+        while let Some(text) = self.peek(0) {
+            match text {
+                ";" => return self.bump(),
+                "{" => {
+                    let idx = self.skip_group();
+                    if self.peek(0) != Some(";") {
+                        return idx;
+                    }
+                }
+                "[" | "(" => {
+                    self.skip_group();
+                }
+                "}" => return self.idx.saturating_sub(1),
+                _ => {
+                    self.bump();
+                }
+            }
+        }
+
+        self.idx.saturating_sub(1)
+    }
+
+    /// Returns the location of a declaration from its first token to its last one, without
+    /// comments.
+    fn get_location(&self, path: Vec<i32>, first: usize, last: usize) -> Location {
+        let (start_line, start_column) = self.tokens[first].start;
+        let (end_line, end_column) = self.tokens[last].end;
+        let span = match start_line == end_line {
+            true => vec![start_line, start_column, end_column],
+            false => vec![start_line, start_column, end_line, end_column],
+        };
+
+        Location {
+            path,
+            span,
+            ..Default::default()
+        }
+    }
+
+    /// Records the location of a declaration from its first token to its last one, its trailing
+    /// comments are the ones of the token ending its declaration.
+    fn add_location(&mut self, path: Option<Vec<i32>>, first: usize, end: usize, last: usize) {
+        let Some(path) = path else {
+            return;
+        };
+
+        let location = Location {
+            leading_comments: self.tokens[first].leading_comments.clone(),
+            trailing_comments: self.tokens[end].trailing_comments.clone(),
+            ..self.get_location(path, first, last)
+        };
+        self.locations.push(location);
+    }
+
+    fn parse_file(&mut self, file: &FileDescriptorProto) {
+        while let Some(text) = self.peek(0) {
+            match text {
+                "package" => {
+                    let first = self.idx;
+                    let last = self.skip_statement();
+                    self.add_location(Some(vec![FILE_PACKAGE]), first, last, last);
+                }
+                "message" if self.is_block("message") => {
+                    self.parse_message(&file.message_type, &[], FILE_MESSAGE_TYPE)
+                }
+                "enum" if self.is_block("enum") => {
+                    self.parse_enum(&file.enum_type, &[], FILE_ENUM_TYPE)
+                }
+                "service" if self.is_block("service") => self.parse_service(&file.service),
+                "extend" if self.is_block("extend") => self.parse_extend(Some(FieldScope {
+                    path: &[],
+                    fields: &file.extension,
+                    field_number: FILE_EXTENSION,
+                    nested_types: &file.message_type,
+                    nested_type_number: FILE_MESSAGE_TYPE,
+                })),
+                _ => {
+                    self.skip_statement();
+                }
+            }
+        }
+    }
+
+    fn parse_message(&mut self, messages: &[DescriptorProto], path: &[i32], field_number: i32) {
+        let first = self.bump();
+        let name = self.peek(0).unwrap_or_default().to_string();
+        let open = self.bump() + 1;
+        self.bump();
+
+        let message = messages
+            .iter()
+            .position(|message| message.name() == name)
+            .map(|idx| (&messages[idx], get_child_path(path, field_number, idx)));
+        let last =
+            self.parse_message_body(message.as_ref().map(|(message, path)| (*message, path)));
+
+        self.add_location(message.map(|(_, path)| path), first, open, last);
+    }
+
+    /// Parses the declarations of a message up to its closing `}`, returning its index.
+    fn parse_message_body(&mut self, message: Option<(&DescriptorProto, &Vec<i32>)>) -> usize {
+        let path = message.map(|(_, path)| path.as_slice()).unwrap_or_default();
+        let nested_types = message
+            .map(|(message, _)| message.nested_type.as_slice())
+            .unwrap_or_default();
+        let enum_types = message
+            .map(|(message, _)| message.enum_type.as_slice())
+            .unwrap_or_default();
+        let extension_scope = message.map(|(message, path)| FieldScope {
+            path,
+            fields: &message.extension,
+            field_number: MESSAGE_EXTENSION,
+            nested_types: &message.nested_type,
+            nested_type_number: MESSAGE_NESTED_TYPE,
+        });
+
+        while let Some(text) = self.peek(0) {
+            match text {
+                "}" => return self.bump(),
+                "message" if self.is_block("message") => {
+                    self.parse_message(nested_types, path, MESSAGE_NESTED_TYPE)
+                }
+                "enum" if self.is_block("enum") => {
+                    self.parse_enum(enum_types, path, MESSAGE_ENUM_TYPE)
+                }
+                "oneof" if self.is_block("oneof") => self.parse_oneof(message),
+                "extend" if self.is_block("extend") => self.parse_extend(extension_scope),
+                "option" | "reserved" | "extensions" | ";" => {
+                    self.skip_statement();
+                }
+                _ => self.parse_field(get_field_scope(message)),
+            }
+        }
+
+        self.idx.saturating_sub(1)
+    }
+
+    fn parse_oneof(&mut self, message: Option<(&DescriptorProto, &Vec<i32>)>) {
+        let first = self.bump();
+        let name = self.peek(0).unwrap_or_default().to_string();
+        let open = self.bump() + 1;
+        self.bump();
+
+        let path = message.and_then(|(message, path)| {
+            message
+                .oneof_decl
+                .iter()
+                .position(|oneof| oneof.name() == name)
+                .map(|idx| get_child_path(path, MESSAGE_ONEOF_DECL, idx))
+        });
+
+        let mut last = self.idx.saturating_sub(1);
+        while let Some(text) = self.peek(0) {
+            match text {
+                "}" => {
+                    last = self.bump();
+                    break;
+                }
+                "option" | ";" => {
+                    self.skip_statement();
+                }
+                _ => self.parse_field(get_field_scope(message)),
+            }
+        }
+
+        self.add_location(path, first, open, last);
+    }
+
+    /// Parses an `extend` block, its extensions are fields of the scope.
+    fn parse_extend(&mut self, scope: Option<FieldScope>) {
+        let first = self.bump();
+        let open = self.bump() + 1;
+        self.bump();
+
+        let mut last = self.idx.saturating_sub(1);
+        while let Some(text) = self.peek(0) {
+            match text {
+                "}" => {
+                    last = self.bump();
+                    break;
+                }
+                ";" => {
+                    self.skip_statement();
+                }
+                _ => self.parse_field(scope),
+            }
+        }
+
+        let path = scope.map(|scope| [scope.path, &[scope.field_number]].concat());
+        self.add_location(path, first, open, last);
+    }
+
+    /// Parses a field, a map field or a group up to its `;` or the closing `}` of the group.
+    fn parse_field(&mut self, scope: Option<FieldScope>) {
+        let first = self.idx;
+        let mut name = String::new();
+        let mut is_group = false;
+        while let Some(text) = self.peek(0) {
+            match text {
+                "=" | ";" | "}" => break,
+                "<" => {
+                    while !matches!(self.peek(0), Some(">") | None) {
+                        self.bump();
+                    }
+                }
+                "group" => is_group = true,
+                _ => name = text.to_string(),
+            }
+            self.bump();
+        }
+
+        let mut end = self.idx;
+        let mut last = self.idx;
+        let mut group_path = None;
+        while let Some(text) = self.peek(0) {
+            match text {
+                ";" => {
+                    end = self.bump();
+                    last = end;
+                    break;
+                }
+                "{" => {
+                    end = self.bump();
+                    let nested_type = scope.and_then(|scope| {
+                        scope
+                            .nested_types
+                            .iter()
+                            .position(|nested_type| nested_type.name() == name)
+                            .map(|idx| {
+                                (
+                                    &scope.nested_types[idx],
+                                    get_child_path(scope.path, scope.nested_type_number, idx),
+                                )
+                            })
+                    });
+                    last = self.parse_message_body(
+                        nested_type
+                            .as_ref()
+                            .map(|(nested_type, path)| (*nested_type, path)),
+                    );
+                    group_path = nested_type.map(|(_, path)| path);
+                    break;
+                }
+                "}" => break,
+                "[" => {
+                    self.skip_group();
+                }
+                _ => {
+                    self.bump();
+                }
+            }
+        }
+
+        // Groups declare a field named after the lowercase group name
+        if is_group {
+            name = name.to_lowercase();
+        }
+        let path = scope.and_then(|scope| {
+            scope
+                .fields
+                .iter()
+                .position(|field| field.name() == name)
+                .map(|idx| get_child_path(scope.path, scope.field_number, idx))
+        });
+        if self.tokens.get(last).is_none() {
+            return;
+        }
+
+        // Comments of groups belong to the message they declare, their field spans it too
+        match is_group {
+            true => {
+                if let Some(path) = path {
+                    let location = self.get_location(path, first, last);
+                    self.locations.push(location);
+                }
+                self.add_location(group_path, first, end, last);
+            }
+            false => self.add_location(path, first, end, last),
+        }
+    }
+
+    fn parse_enum(&mut self, enums: &[EnumDescriptorProto], path: &[i32], field_number: i32) {
+        let first = self.bump();
+        let name = self.peek(0).unwrap_or_default().to_string();
+        let open = self.bump() + 1;
+        self.bump();
+
+        let enum_descriptor = enums
+            .iter()
+            .position(|enum_descriptor| enum_descriptor.name() == name)
+            .map(|idx| (&enums[idx], get_child_path(path, field_number, idx)));
+
+        let mut last = self.idx.saturating_sub(1);
+        while let Some(text) = self.peek(0) {
+            match text {
+                "}" => {
+                    last = self.bump();
+                    break;
+                }
+                "option" | "reserved" | ";" => {
+                    self.skip_statement();
+                }
+                value_name => {
+                    let value_path =
+                        enum_descriptor
+                            .as_ref()
+                            .and_then(|(enum_descriptor, path)| {
+                                enum_descriptor
+                                    .value
+                                    .iter()
+                                    .position(|value| value.name() == value_name)
+                                    .map(|idx| get_child_path(path, ENUM_VALUE, idx))
+                            });
+                    let value_first = self.idx;
+                    let value_last = self.skip_statement();
+                    self.add_location(value_path, value_first, value_last, value_last);
+                }
+            }
+        }
+
+        self.add_location(enum_descriptor.map(|(_, path)| path), first, open, last);
+    }
+
+    fn parse_service(&mut self, services: &[ServiceDescriptorProto]) {
+        let first = self.bump();
+        let name = self.peek(0).unwrap_or_default().to_string();
+        let open = self.bump() + 1;
+        self.bump();
+
+        let service = services
+            .iter()
+            .position(|service| service.name() == name)
+            .map(|idx| (&services[idx], get_child_path(&[], FILE_SERVICE, idx)));
+
+        let mut last = self.idx.saturating_sub(1);
+        while let Some(text) = self.peek(0) {
+            match text {
+                "}" => {
+                    last = self.bump();
+                    break;
+                }
+                "rpc" => {
+                    let method_first = self.bump();
+                    let method_name = self.peek(0).unwrap_or_default().to_string();
+                    let method_path = service.as_ref().and_then(|(service, path)| {
+                        service
+                            .method
+                            .iter()
+                            .position(|method| method.name() == method_name)
+                            .map(|idx| get_child_path(path, SERVICE_METHOD, idx))
+                    });
+
+                    // Methods end with `;` or with the `}` of their options
+                    while !matches!(self.peek(0), Some(";" | "{") | None) {
+                        self.bump();
+                    }
+                    let method_end = self.idx;
+                    let method_last = match self.peek(0) {
+                        Some("{") => self.skip_group(),
+                        _ => self.bump(),
+                    };
+                    if self.tokens.get(method_last).is_some() {
+                        self.add_location(method_path, method_first, method_end, method_last);
+                    }
+                }
+                _ => {
+                    self.skip_statement();
+                }
+            }
+        }
+
+        self.add_location(service.map(|(_, path)| path), first, open, last);
+    }
+}
+
+/// Returns the scope of the fields of a message.
+fn get_field_scope<'d>(
+    message: Option<(&'d DescriptorProto, &'d Vec<i32>)>,
+) -> Option<FieldScope<'d>> {
+    message.map(|(message, path)| FieldScope {
+        path,
+        fields: &message.field,
+        field_number: MESSAGE_FIELD,
+        nested_types: &message.nested_type,
+        nested_type_number: MESSAGE_NESTED_TYPE,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost_types::{
+        EnumValueDescriptorProto, FieldDescriptorProto, MethodDescriptorProto, OneofDescriptorProto,
+    };
+
+    fn get_location<'a>(source_code_info: &'a SourceCodeInfo, path: &[i32]) -> &'a Location {
+        source_code_info
+            .location
+            .iter()
+            .find(|location| location.path == path)
+            .unwrap()
+    }
+
+    /// Path, span, leading and trailing comments of a location.
+    type ExpectedLocation<'a> = (&'a [i32], &'a [i32], Option<&'a str>, Option<&'a str>);
+
+    /// Returns the `SourceCodeInfo` of a source, along with its descriptors from the parser.
+    fn get_parsed_source_code_info(name: &str, source: &str) -> SourceCodeInfo {
+        let include = std::env::temp_dir().join(format!(
+            "prutoipa-source-code-info-{name}-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&include).unwrap();
+        std::fs::write(include.join("source.proto"), source).unwrap();
+
+        let parsed = protobuf_parse::Parser::new()
+            .pure()
+            .include(&include)
+            .input(include.join("source.proto"))
+            .parse_and_typecheck()
+            .unwrap();
+        std::fs::remove_dir_all(&include).unwrap();
+
+        let file = parsed
+            .file_descriptors
+            .into_iter()
+            .find(|file| file.name() == "source.proto")
+            .unwrap();
+        let file_encoded = protobuf::Message::write_to_bytes(&file).unwrap();
+
+        get_source_code_info(
+            source,
+            &prost::Message::decode(file_encoded.as_slice()).unwrap(),
+        )
+    }
+
+    fn get_field(name: &str) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn comments() {
+        let source = [
+            "// Detached file comment.",
+            "",
+            "// Syntax comment.",
+            "syntax = \"proto3\";",
+            "",
+            "// The people package.",
+            "package people;",
+            "",
+            "/*",
+            " * A person.",
+            " *",
+            " * Known by its id.",
+            " */",
+            "message Person { // Trailing person comment.",
+            "  // The id.",
+            "  int32 id = 1; // Its id.",
+            "  map<string, string> labels = 2 [deprecated = true];",
+            "  // Trailing labels comment.",
+            "",
+            "  // Detached comment.",
+            "",
+            "  oneof contact {",
+            "    /* Email. */ string email = 3;",
+            "    string phone = 4;",
+            "  }",
+            "",
+            "  message Address {",
+            "    // The street.",
+            "    string street = 1;",
+            "  }",
+            "}",
+            "",
+            "// The gender.",
+            "enum Gender {",
+            "  // Male.",
+            "  MALE = 0;",
+            "  FEMALE = 1; // Female.",
+            "}",
+            "",
+            "service People {",
+            "  // Gets a person.",
+            "  rpc GetPerson(Person) returns (Person) {",
+            "    option (google.api.http) = { get: \"/v1/people/{id}\" };",
+            "  }",
+            "  rpc ListPeople(Person) returns (stream Person);",
+            "}",
+        ]
+        .join("\n");
+        let file = FileDescriptorProto {
+            message_type: vec![DescriptorProto {
+                name: Some("Person".to_string()),
+                field: vec![
+                    get_field("id"),
+                    get_field("labels"),
+                    get_field("email"),
+                    get_field("phone"),
+                ],
+                nested_type: vec![
+                    DescriptorProto {
+                        name: Some("LabelsEntry".to_string()),
+                        ..Default::default()
+                    },
+                    DescriptorProto {
+                        name: Some("Address".to_string()),
+                        field: vec![get_field("street")],
+                        ..Default::default()
+                    },
+                ],
+                oneof_decl: vec![OneofDescriptorProto {
+                    name: Some("contact".to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            enum_type: vec![EnumDescriptorProto {
+                name: Some("Gender".to_string()),
+                value: ["MALE", "FEMALE"]
+                    .into_iter()
+                    .map(|name| EnumValueDescriptorProto {
+                        name: Some(name.to_string()),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            }],
+            service: vec![ServiceDescriptorProto {
+                name: Some("People".to_string()),
+                method: ["GetPerson", "ListPeople"]
+                    .into_iter()
+                    .map(|name| MethodDescriptorProto {
+                        name: Some(name.to_string()),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let source_code_info = get_source_code_info(&source, &file);
+        let comments = |path: &[i32]| {
+            let location = get_location(&source_code_info, path);
+            (
+                location.leading_comments.as_deref(),
+                location.trailing_comments.as_deref(),
+            )
+        };
+
+        assert_eq!(comments(&[2]), (Some(" The people package.\n"), None));
+        assert_eq!(
+            comments(&[4, 0]),
+            (
+                Some("\n A person.\n\n Known by its id.\n"),
+                Some(" Trailing person comment.\n")
+            )
+        );
+        assert_eq!(
+            comments(&[4, 0, 2, 0]),
+            (Some(" The id.\n"), Some(" Its id.\n"))
+        );
+        assert_eq!(
+            comments(&[4, 0, 2, 1]),
+            (None, Some(" Trailing labels comment.\n"))
+        );
+        assert_eq!(comments(&[4, 0, 8, 0]), (None, None));
+        assert_eq!(comments(&[4, 0, 2, 2]), (Some(" Email. "), None));
+        assert_eq!(comments(&[4, 0, 3, 1]), (None, None));
+        assert_eq!(
+            comments(&[4, 0, 3, 1, 2, 0]),
+            (Some(" The street.\n"), None)
+        );
+        assert_eq!(comments(&[5, 0]), (Some(" The gender.\n"), None));
+        assert_eq!(comments(&[5, 0, 2, 0]), (Some(" Male.\n"), None));
+        assert_eq!(comments(&[5, 0, 2, 1]), (None, Some(" Female.\n")));
+        assert_eq!(comments(&[6, 0, 2, 0]), (Some(" Gets a person.\n"), None));
+        assert_eq!(comments(&[6, 0, 2, 1]), (None, None));
+
+        assert_eq!(
+            get_location(&source_code_info, &[4, 0]).span,
+            vec![13, 0, 30, 1]
+        );
+        assert_eq!(
+            get_location(&source_code_info, &[4, 0, 2, 0]).span,
+            vec![15, 2, 15]
+        );
+        assert_eq!(
+            get_location(&source_code_info, &[6, 0, 2, 0]).span,
+            vec![41, 2, 43, 3]
+        );
+    }
+
+    #[test]
+    fn constructs() {
+        let source = [
+            "syntax = \"proto2\";",
+            "",
+            "package library;",
+            "",
+            "import \"google/protobuf/descriptor.proto\";",
+            "",
+            "// A rule.",
+            "message Rule {",
+            "  optional string path = 1 [default = \"/{id}\"]; // Its path.",
+            "  optional Rule nested = 2;",
+            "}",
+            "",
+            "// Method rules.",
+            "extend google.protobuf.MethodOptions { // Trailing extend comment.",
+            "  // The method rule.",
+            "  optional Rule rule = 50000;",
+            "}",
+            "",
+            "extend google.protobuf.MessageOptions {",
+            "  optional Rule message_rule = 50001; // The message rule.",
+            "}",
+            "",
+            "// A book.",
+            "message Book {",
+            "  option (message_rule) = { path: \"}\" nested { path: \"{\" } };",
+            "  // The title.",
+            "  required string title = 1;",
+            "  // The ratings.",
+            "  map<string, int32> ratings = 2; // By reader.",
+            "  // The chapters.",
+            "  repeated group Chapter = 3 { // Trailing chapter comment.",
+            "    // The chapter title.",
+            "    optional string title = 1;",
+            "  }",
+            "  // Reserved for extensions.",
+            "  extensions 100 to 199;",
+            "  // The pages.",
+            "  optional int32 pages = 4;",
+            "}",
+            "",
+            "extend Book {",
+            "  // The edition.",
+            "  optional int32 edition = 100;",
+            "}",
+            "",
+            "message Shelf {",
+            "  extend Book {",
+            "    // The shelf.",
+            "    optional Shelf shelf = 101;",
+            "  }",
+            "}",
+            "",
+            "service Books {",
+            "  // Gets a book.",
+            "  rpc GetBook(Book) returns (Book) { // Trailing get comment.",
+            "    option (rule) = { path: \"/v1/books/{title}\" nested { path: \"}\" } };",
+            "  }",
+            "  // Lists the books.",
+            "  rpc ListBooks(Book) returns (stream Book);",
+            "}",
+        ]
+        .join("\n");
+        let source_code_info = get_parsed_source_code_info("constructs", &source);
+
+        let expected_locations: [ExpectedLocation; 21] = [
+            (&[2], &[2, 0, 16], None, None),
+            (&[4, 0], &[7, 0, 10, 1], Some(" A rule.\n"), None),
+            (&[4, 0, 2, 0], &[8, 2, 47], None, Some(" Its path.\n")),
+            (&[4, 0, 2, 1], &[9, 2, 27], None, None),
+            // Extensions are numbered across the `extend` blocks of their scope
+            (
+                &[7],
+                &[13, 0, 16, 1],
+                Some(" Method rules.\n"),
+                Some(" Trailing extend comment.\n"),
+            ),
+            (&[7, 0], &[15, 2, 29], Some(" The method rule.\n"), None),
+            (&[7, 1], &[19, 2, 37], None, Some(" The message rule.\n")),
+            (&[7, 2], &[42, 2, 31], Some(" The edition.\n"), None),
+            // Aggregate option values do not end the message
+            (&[4, 1], &[23, 0, 38, 1], Some(" A book.\n"), None),
+            (&[4, 1, 2, 0], &[26, 2, 28], Some(" The title.\n"), None),
+            (
+                &[4, 1, 2, 1],
+                &[28, 2, 33],
+                Some(" The ratings.\n"),
+                Some(" By reader.\n"),
+            ),
+            // Comments of a group belong to its message, its field spans the same declaration
+            (&[4, 1, 2, 2], &[30, 2, 33, 3], None, None),
+            (
+                &[4, 1, 3, 1],
+                &[30, 2, 33, 3],
+                Some(" The chapters.\n"),
+                Some(" Trailing chapter comment.\n"),
+            ),
+            (
+                &[4, 1, 3, 1, 2, 0],
+                &[32, 4, 30],
+                Some(" The chapter title.\n"),
+                None,
+            ),
+            (&[4, 1, 2, 3], &[37, 2, 27], Some(" The pages.\n"), None),
+            (&[4, 2], &[45, 0, 50, 1], None, None),
+            (&[4, 2, 6], &[46, 2, 49, 3], None, None),
+            (&[4, 2, 6, 0], &[48, 4, 31], Some(" The shelf.\n"), None),
+            (&[6, 0], &[52, 0, 59, 1], None, None),
+            // Option bodies of methods end at their closing `}`, strings apart
+            (
+                &[6, 0, 2, 0],
+                &[54, 2, 56, 3],
+                Some(" Gets a book.\n"),
+                Some(" Trailing get comment.\n"),
+            ),
+            (
+                &[6, 0, 2, 1],
+                &[58, 2, 44],
+                Some(" Lists the books.\n"),
+                None,
+            ),
+        ];
+
+        for (path, span, leading_comments, trailing_comments) in expected_locations {
+            let location = get_location(&source_code_info, path);
+
+            assert_eq!(location.span, span, "{path:?}");
+            assert_eq!(
+                location.leading_comments.as_deref(),
+                leading_comments,
+                "{path:?}"
+            );
+            assert_eq!(
+                location.trailing_comments.as_deref(),
+                trailing_comments,
+                "{path:?}"
+            );
+        }
+
+        // Map entries are synthetic, they are not declared at the source
+        assert!(!source_code_info
+            .location
+            .iter()
+            .any(|location| location.path == [4, 1, 3, 0]));
+    }
+}
@@ -1,5 +1,7 @@
+pub mod comments;
 pub mod enum_descriptor;
 pub mod message_descriptor;
+pub mod service_descriptor;
 
 use crate::descriptor::{enum_descriptor::EnumDescriptor, message_descriptor::MessageDescriptor};
 
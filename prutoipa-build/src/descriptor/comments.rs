@@ -0,0 +1,219 @@
+use prost_types::{source_code_info::Location, SourceCodeInfo};
+use std::collections::BTreeMap;
+
+// Field numbers of the descriptors, used to build `SourceCodeInfo` paths
+pub const FILE_PACKAGE: i32 = 2;
+pub const FILE_MESSAGE_TYPE: i32 = 4;
+pub const FILE_ENUM_TYPE: i32 = 5;
+pub const FILE_SERVICE: i32 = 6;
+pub const FILE_EXTENSION: i32 = 7;
+pub const MESSAGE_FIELD: i32 = 2;
+pub const MESSAGE_NESTED_TYPE: i32 = 3;
+pub const MESSAGE_ENUM_TYPE: i32 = 4;
+pub const MESSAGE_EXTENSION: i32 = 6;
+pub const MESSAGE_ONEOF_DECL: i32 = 8;
+pub const ENUM_VALUE: i32 = 2;
+pub const SERVICE_METHOD: i32 = 2;
+
+/// Leading and trailing comments of a descriptor, detached comments are ignored.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Comments {
+    leading: Vec<String>,
+    trailing: Vec<String>,
+}
+
+/// Comments and positions of every descriptor of a file, keyed by their `SourceCodeInfo` path.
+#[derive(Debug, Clone, Default)]
+pub struct FileComments {
+    comments: BTreeMap<Vec<i32>, Comments>,
+    positions: BTreeMap<Vec<i32>, (i32, i32)>,
+}
+
+impl Comments {
+    pub fn new(location: &Location) -> Self {
+        Self {
+            leading: get_lines(location.leading_comments.as_deref()),
+            trailing: get_lines(location.trailing_comments.as_deref()),
+        }
+    }
+
+    //
+    /// Returns the comments as a description. Unless `markdown` is set, lines are unwrapped so
+    /// only blank lines break paragraphs.
+    pub fn get_description(&self, markdown: bool) -> Option<String> {
+        let paragraphs = [&self.leading, &self.trailing]
+            .into_iter()
+            .filter(|lines| !lines.is_empty())
+            .map(|lines| match markdown {
+                true => lines.join("\n"),
+                false => lines
+                    .split(|line| line.is_empty())
+                    .filter(|paragraph| !paragraph.is_empty())
+                    .map(|paragraph| {
+                        paragraph
+                            .iter()
+                            .map(|line| line.trim())
+                            .collect::<Vec<&str>>()
+                            .join(" ")
+                    })
+                    .collect::<Vec<String>>()
+                    .join("\n\n"),
+            })
+            .collect::<Vec<String>>();
+
+        match paragraphs.is_empty() {
+            true => None,
+            false => Some(paragraphs.join("\n\n")),
+        }
+    }
+}
+
+impl FileComments {
+    pub fn new(source_code_info: Option<&SourceCodeInfo>) -> Self {
+        let locations = source_code_info
+            .map(|source_code_info| source_code_info.location.as_slice())
+            .unwrap_or_default();
+
+        let comments = locations
+            .iter()
+            .map(|location| (location.path.clone(), Comments::new(location)))
+            .filter(|(_, comments)| *comments != Comments::default())
+            .collect();
+        // Spans are zero-based, starting with the line and column
+        let positions = locations
+            .iter()
+            .filter_map(|location| match location.span.as_slice() {
+                [line, column, ..] => Some((location.path.clone(), (line + 1, column + 1))),
+                _ => None,
+            })
+            .collect();
+
+        Self {
+            comments,
+            positions,
+        }
+    }
+
+    //
+    pub fn get_comments(&self, path: &[i32]) -> Comments {
+        self.comments.get(path).cloned().unwrap_or_default()
+    }
+
+    /// Returns the one-based line and column the descriptor at `path` starts at.
+    pub fn get_position(&self, path: &[i32]) -> Option<(i32, i32)> {
+        self.positions.get(path).copied()
+    }
+}
+
+pub fn get_child_path(path: &[i32], field_number: i32, index: usize) -> Vec<i32> {
+    let mut child_path = path.to_vec();
+    child_path.push(field_number);
+    child_path.push(index as i32);
+
+    child_path
+}
+
+/// Splits a comment in lines, removing the indentation they share and surrounding blank lines.
+fn get_lines(comment: Option<&str>) -> Vec<String> {
+    let lines = comment
+        .unwrap_or_default()
+        .lines()
+        .map(|line| line.trim_end())
+        .collect::<Vec<&str>>();
+    let indentation = lines
+        .iter()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or_default();
+
+    let mut lines = lines
+        .into_iter()
+        .map(|line| line.get(indentation..).unwrap_or_default().to_string())
+        .skip_while(|line| line.is_empty())
+        .collect::<Vec<String>>();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_comments(leading: &str, trailing: Option<&str>) -> Comments {
+        Comments::new(&Location {
+            leading_comments: Some(leading.to_string()),
+            trailing_comments: trailing.map(str::to_string),
+            leading_detached_comments: vec![" Detached comment.\n".to_string()],
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn comments_description() {
+        let comments = get_comments(
+            " A person.\n Known by\n   its name.\n\n Second paragraph.\n",
+            Some(" Trailing comment.\n"),
+        );
+
+        assert_eq!(
+            comments.get_description(false),
+            Some(
+                "A person. Known by its name.\n\nSecond paragraph.\n\nTrailing comment."
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            comments.get_description(true),
+            Some(
+                "A person.\nKnown by\n  its name.\n\nSecond paragraph.\n\nTrailing comment."
+                    .to_string()
+            )
+        );
+        assert_eq!(Comments::default().get_description(false), None);
+    }
+
+    #[test]
+    fn file_comments() {
+        let file_comments = FileComments::new(Some(&SourceCodeInfo {
+            location: vec![
+                Location {
+                    path: vec![FILE_MESSAGE_TYPE, 0],
+                    leading_comments: Some(" A person.\n".to_string()),
+                    ..Default::default()
+                },
+                Location {
+                    path: get_child_path(&[FILE_MESSAGE_TYPE, 0], MESSAGE_FIELD, 1),
+                    span: vec![4, 2, 30],
+                    trailing_comments: Some(" Its name.\n".to_string()),
+                    ..Default::default()
+                },
+            ],
+        }));
+
+        assert_eq!(
+            file_comments
+                .get_comments(&[FILE_MESSAGE_TYPE, 0])
+                .get_description(false),
+            Some("A person.".to_string())
+        );
+        assert_eq!(
+            file_comments
+                .get_comments(&[FILE_MESSAGE_TYPE, 0, MESSAGE_FIELD, 1])
+                .get_description(false),
+            Some("Its name.".to_string())
+        );
+        assert_eq!(
+            file_comments.get_comments(&[FILE_ENUM_TYPE, 0]),
+            Comments::default()
+        );
+        assert_eq!(
+            file_comments.get_position(&[FILE_MESSAGE_TYPE, 0, MESSAGE_FIELD, 1]),
+            Some((5, 3))
+        );
+        assert_eq!(file_comments.get_position(&[FILE_MESSAGE_TYPE, 0]), None);
+    }
+}
@@ -1,29 +1,83 @@
-use prost_types::EnumValueDescriptorProto;
+use prost_types::EnumDescriptorProto;
+
+use crate::{
+    descriptor::comments::{get_child_path, Comments, FileComments, ENUM_VALUE},
+    error::{ErrorLocation, PrutoipaBuildError},
+};
 
 #[derive(Debug, Clone)]
 pub struct EnumDescriptor {
-    values: Vec<EnumValueDescriptorProto>,
+    values: Vec<EnumValue>,
+    comments: Comments,
+    deprecated: bool,
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct EnumValue {
     pub name: String,
     pub number: i32,
+    pub comments: Comments,
+    pub deprecated: bool,
 }
 
 impl EnumDescriptor {
-    pub fn new(values: Vec<EnumValueDescriptorProto>) -> Self {
-        Self { values }
+    pub fn new(
+        package_name: String,
+        path: String,
+        descriptor: EnumDescriptorProto,
+        file_comments: &FileComments,
+        location: &[i32],
+    ) -> Result<Self, PrutoipaBuildError> {
+        let scope = format!("{package_name}.{path}");
+        let values = descriptor
+            .value
+            .into_iter()
+            .enumerate()
+            .map(|(idx, evdp)| {
+                let value_location = get_child_path(location, ENUM_VALUE, idx);
+                let name = evdp
+                    .name
+                    .clone()
+                    .ok_or_else(|| PrutoipaBuildError::MissingName {
+                        location: ErrorLocation::new(
+                            scope.clone(),
+                            file_comments.get_position(&value_location),
+                        )
+                        .into(),
+                        kind: "enum value",
+                    })?;
+
+                Ok(EnumValue {
+                    name,
+                    number: evdp.number(),
+                    comments: file_comments.get_comments(&value_location),
+                    deprecated: evdp
+                        .options
+                        .as_ref()
+                        .is_some_and(|options| options.deprecated()),
+                })
+            })
+            .collect::<Result<Vec<EnumValue>, PrutoipaBuildError>>()?;
+
+        Ok(Self {
+            values,
+            comments: file_comments.get_comments(location),
+            deprecated: descriptor
+                .options
+                .as_ref()
+                .is_some_and(|options| options.deprecated()),
+        })
     }
 
     pub fn get_values(&self) -> Vec<EnumValue> {
-        self.values
-            .clone()
-            .into_iter()
-            .map(|evdp| EnumValue {
-                name: evdp.name().to_string(),
-                number: evdp.number(),
-            })
-            .collect::<Vec<EnumValue>>()
+        self.values.clone()
+    }
+
+    pub fn get_comments(&self) -> Comments {
+        self.comments.clone()
+    }
+
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated
     }
 }
@@ -1,41 +1,150 @@
+pub mod default_value;
 pub mod field;
+pub mod oneof;
+pub mod well_known;
 
 use prost_types::DescriptorProto;
+use std::collections::BTreeMap;
 
 use crate::{
-    descriptor::message_descriptor::field::Field, error::PrutoipaBuildError, syntax::Syntax,
+    descriptor::{
+        comments::{get_child_path, Comments, FileComments, MESSAGE_FIELD, MESSAGE_ONEOF_DECL},
+        message_descriptor::{field::Field, oneof::OneOf},
+    },
+    error::{ErrorLocation, PrutoipaBuildError},
+    resolver::Resolver,
+    syntax::Syntax,
 };
 
 #[derive(Debug, Clone)]
 pub struct MessageDescriptor {
     fields: Vec<Field>,
+    oneofs: Vec<OneOf>,
+    comments: Comments,
+    deprecated: bool,
 }
 
 impl MessageDescriptor {
-    pub fn new(syntax: Syntax, descriptor: DescriptorProto) -> Result<Self, PrutoipaBuildError> {
+    pub fn new(
+        syntax: Syntax,
+        package_name: String,
+        path: String,
+        descriptor: DescriptorProto,
+        file_comments: &FileComments,
+        location: &[i32],
+    ) -> Result<Self, PrutoipaBuildError> {
         let mut fields: Vec<Field> = Vec::new();
+        let scope = format!("{package_name}.{path}");
+        let map_entries = descriptor
+            .nested_type
+            .iter()
+            .filter(|nested_type| {
+                nested_type
+                    .options
+                    .as_ref()
+                    .and_then(|options| options.map_entry)
+                    .unwrap_or(false)
+            })
+            .map(|nested_type| {
+                (
+                    format!(".{scope}.{}", nested_type.name()),
+                    nested_type.clone(),
+                )
+            })
+            .collect::<BTreeMap<String, DescriptorProto>>();
+        let mut oneofs = descriptor
+            .oneof_decl
+            .iter()
+            .enumerate()
+            .map(|(idx, oneof_descriptor_proto)| {
+                let oneof_location = get_child_path(location, MESSAGE_ONEOF_DECL, idx);
+                let comments = file_comments.get_comments(&oneof_location);
 
-        for field_descriptor_proto in &descriptor.field {
-            let field = Field::new(&syntax, field_descriptor_proto)?;
+                oneof_descriptor_proto
+                    .name
+                    .clone()
+                    .map(|name| OneOf::new(name, comments))
+                    .ok_or_else(|| PrutoipaBuildError::MissingName {
+                        location: ErrorLocation::new(
+                            scope.clone(),
+                            file_comments.get_position(&oneof_location),
+                        )
+                        .into(),
+                        kind: "one of",
+                    })
+            })
+            .collect::<Result<Vec<OneOf>, PrutoipaBuildError>>()?;
+
+        for (idx, field_descriptor_proto) in descriptor.field.iter().enumerate() {
+            let field_location = get_child_path(location, MESSAGE_FIELD, idx);
+            let field = Field::new(
+                &syntax,
+                &scope,
+                field_descriptor_proto,
+                &map_entries,
+                file_comments.get_comments(&field_location),
+                file_comments.get_position(&field_location),
+            )?;
 
             // Treat synthetic one-of as normal
             let proto3_optional = field_descriptor_proto.proto3_optional.unwrap_or(false);
             match (field_descriptor_proto.oneof_index, proto3_optional) {
-                (Some(_idx), false) => {
-                    println!(
-                        "{}",
-                        PrutoipaBuildError::NotImplementedYet("One of".to_string())
-                    )
-                }
+                (Some(idx), false) => oneofs
+                    .get_mut(idx as usize)
+                    .ok_or_else(|| PrutoipaBuildError::InvalidField {
+                        location: field.get_error_location().into(),
+                        reason: format!("one of index {idx} is out of range"),
+                    })?
+                    .push_field(field),
                 _ => fields.push(field),
             }
         }
 
-        Ok(Self { fields })
+        // Synthetic one-ofs end up without fields
+        oneofs.retain(|oneof| !oneof.get_fields().is_empty());
+
+        Ok(Self {
+            fields,
+            oneofs,
+            comments: file_comments.get_comments(location),
+            deprecated: descriptor
+                .options
+                .as_ref()
+                .is_some_and(|options| options.deprecated()),
+        })
     }
 
     //
     pub fn get_fields(&self) -> Vec<Field> {
         self.fields.clone()
     }
+
+    pub fn get_oneofs(&self) -> Vec<OneOf> {
+        self.oneofs.clone()
+    }
+
+    pub fn get_comments(&self) -> Comments {
+        self.comments.clone()
+    }
+
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated
+    }
+
+    //
+    pub fn resolve(self, resolver: &Resolver) -> Result<Self, PrutoipaBuildError> {
+        Ok(Self {
+            fields: self
+                .fields
+                .into_iter()
+                .map(|field| field.resolve(resolver))
+                .collect::<Result<Vec<Field>, PrutoipaBuildError>>()?,
+            oneofs: self
+                .oneofs
+                .into_iter()
+                .map(|oneof| oneof.resolve(resolver))
+                .collect::<Result<Vec<OneOf>, PrutoipaBuildError>>()?,
+            ..self
+        })
+    }
 }
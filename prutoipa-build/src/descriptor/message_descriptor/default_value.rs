@@ -0,0 +1,218 @@
+use crate::{
+    descriptor::message_descriptor::field::ScalarType,
+    error::{ErrorLocation, PrutoipaBuildError},
+};
+
+/// Default value of a field, as `protoc` writes it at `FieldDescriptorProto::default_value` or
+/// the zero value of fields with implicit presence.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DefaultValue {
+    Int32(i32),
+    Int64(i64),
+    UInt32(u32),
+    UInt64(u64),
+    Float(f64),
+    Bool(bool),
+    String(String),
+    Bytes(Vec<u8>),
+    Enum { name: String, number: i32 },
+    EnumName(String),
+    EnumNumber(i32),
+}
+
+impl DefaultValue {
+    pub fn new(scalar_type: ScalarType, default_value: &str) -> Result<Self, PrutoipaBuildError> {
+        let invalid = || PrutoipaBuildError::InvalidDefaultValue {
+            location: ErrorLocation::default().into(),
+            default_value: default_value.to_string(),
+            reason: format!("not a valid {scalar_type:?}"),
+        };
+
+        match scalar_type {
+            ScalarType::I32 => default_value
+                .parse()
+                .map(Self::Int32)
+                .map_err(|_| invalid()),
+            ScalarType::I64 => default_value
+                .parse()
+                .map(Self::Int64)
+                .map_err(|_| invalid()),
+            ScalarType::U32 => default_value
+                .parse()
+                .map(Self::UInt32)
+                .map_err(|_| invalid()),
+            ScalarType::U64 => default_value
+                .parse()
+                .map(Self::UInt64)
+                .map_err(|_| invalid()),
+            ScalarType::F32 | ScalarType::F64 => match default_value {
+                "inf" => Ok(Self::Float(f64::INFINITY)),
+                "-inf" => Ok(Self::Float(f64::NEG_INFINITY)),
+                "nan" => Ok(Self::Float(f64::NAN)),
+                _ => default_value
+                    .parse()
+                    .map(Self::Float)
+                    .map_err(|_| invalid()),
+            },
+            ScalarType::Bool => default_value.parse().map(Self::Bool).map_err(|_| invalid()),
+            ScalarType::String => Ok(Self::String(default_value.to_string())),
+            ScalarType::Bytes => unescape_bytes(default_value)
+                .map(Self::Bytes)
+                .ok_or_else(invalid),
+        }
+    }
+
+    /// Returns the zero value scalar fields with implicit presence take when not set.
+    pub fn get_zero(scalar_type: ScalarType) -> Self {
+        match scalar_type {
+            ScalarType::I32 => Self::Int32(0),
+            ScalarType::I64 => Self::Int64(0),
+            ScalarType::U32 => Self::UInt32(0),
+            ScalarType::U64 => Self::UInt64(0),
+            ScalarType::F32 | ScalarType::F64 => Self::Float(0.0),
+            ScalarType::Bool => Self::Bool(false),
+            ScalarType::String => Self::String(String::new()),
+            ScalarType::Bytes => Self::Bytes(Vec::new()),
+        }
+    }
+}
+
+/// Reverts the C escaping `protoc` applies to default values of bytes fields.
+fn unescape_bytes(value: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::<u8>::new();
+    let mut chars = value.bytes().peekable();
+
+    while let Some(byte) = chars.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+
+        let escaped = match chars.next()? {
+            b'n' => b'\n',
+            b'r' => b'\r',
+            b't' => b'\t',
+            b'a' => 0x07,
+            b'b' => 0x08,
+            b'f' => 0x0c,
+            b'v' => 0x0b,
+            b'x' => {
+                let mut number = 0u8;
+                let mut digits = 0;
+                while let Some(digit) = chars.peek().and_then(|c| (*c as char).to_digit(16)) {
+                    if digits == 2 {
+                        break;
+                    }
+                    number = number * 16 + digit as u8;
+                    digits += 1;
+                    chars.next();
+                }
+
+                match digits {
+                    0 => return None,
+                    _ => number,
+                }
+            }
+            digit @ b'0'..=b'7' => {
+                let mut number = (digit - b'0') as u32;
+                let mut digits = 1;
+                while let Some(digit) = chars.peek().and_then(|c| (*c as char).to_digit(8)) {
+                    if digits == 3 {
+                        break;
+                    }
+                    number = number * 8 + digit;
+                    digits += 1;
+                    chars.next();
+                }
+
+                u8::try_from(number).ok()?
+            }
+            other => other,
+        };
+
+        bytes.push(escaped);
+    }
+
+    Some(bytes)
+}
+
+/// Encodes bytes as standard base64 with padding, as proto3 JSON does.
+pub fn to_base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    bytes
+        .chunks(3)
+        .flat_map(|chunk| {
+            let block = chunk.iter().enumerate().fold(0u32, |block, (idx, byte)| {
+                block | (*byte as u32) << (16 - 8 * idx)
+            });
+
+            (0..4).map(move |idx| match idx <= chunk.len() {
+                true => ALPHABET[(block >> (18 - 6 * idx) & 0x3f) as usize] as char,
+                false => '=',
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_values() {
+        assert_eq!(
+            DefaultValue::new(ScalarType::I32, "-42").unwrap(),
+            DefaultValue::Int32(-42)
+        );
+        assert_eq!(
+            DefaultValue::new(ScalarType::U64, "18446744073709551615").unwrap(),
+            DefaultValue::UInt64(u64::MAX)
+        );
+        assert_eq!(
+            DefaultValue::new(ScalarType::F64, "-inf").unwrap(),
+            DefaultValue::Float(f64::NEG_INFINITY)
+        );
+        assert_eq!(
+            DefaultValue::new(ScalarType::F32, "1.5").unwrap(),
+            DefaultValue::Float(1.5)
+        );
+        assert_eq!(
+            DefaultValue::new(ScalarType::Bool, "true").unwrap(),
+            DefaultValue::Bool(true)
+        );
+        assert_eq!(
+            DefaultValue::new(ScalarType::String, "a \"b\"").unwrap(),
+            DefaultValue::String("a \"b\"".to_string())
+        );
+        assert_eq!(
+            DefaultValue::new(ScalarType::Bytes, "a\\001\\x7f\\n\\\\").unwrap(),
+            DefaultValue::Bytes(vec![b'a', 1, 0x7f, b'\n', b'\\'])
+        );
+        assert!(matches!(
+            DefaultValue::new(ScalarType::F64, "nan").unwrap(),
+            DefaultValue::Float(value) if value.is_nan()
+        ));
+    }
+
+    #[test]
+    fn invalid_default_value() {
+        let err = DefaultValue::new(ScalarType::U32, "-1").err().unwrap();
+
+        assert!(matches!(
+            &err,
+            PrutoipaBuildError::InvalidDefaultValue { default_value, reason, .. }
+                if default_value == "-1" && reason == "not a valid U32"
+        ));
+        assert_eq!(err.get_location(), Some(&ErrorLocation::default()));
+    }
+
+    #[test]
+    fn base64() {
+        assert_eq!(to_base64(b""), "");
+        assert_eq!(to_base64(b"f"), "Zg==");
+        assert_eq!(to_base64(b"fo"), "Zm8=");
+        assert_eq!(to_base64(b"foo"), "Zm9v");
+        assert_eq!(to_base64(b"foobar"), "Zm9vYmFy");
+    }
+}
@@ -1,77 +1,201 @@
-use heck::ToSnakeCase;
 use prost_types::{
     field_descriptor_proto::{Label, Type},
-    FieldDescriptorProto,
+    DescriptorProto, FieldDescriptorProto,
 };
+use std::collections::BTreeMap;
 
-use crate::{error::PrutoipaBuildError, syntax::Syntax};
+use crate::{
+    descriptor::{
+        comments::Comments,
+        message_descriptor::{
+            default_value::DefaultValue,
+            well_known::{WellKnownType, WELL_KNOWN_PACKAGE},
+        },
+    },
+    error::{ErrorLocation, PrutoipaBuildError},
+    ident::to_json_name,
+    resolver::{DescriptorKind, Resolver},
+    schema::{KnownFormat, SchemaType},
+    syntax::Syntax,
+};
 
 #[derive(Debug, Clone)]
 pub struct Field {
     name: String,
+    full_name: String,
+    json_name: String,
     field_modifier: FieldModifier,
+    field_presence: FieldPresence,
     field_type: FieldType,
+    default_value: Option<DefaultValue>,
+    implicit_default_value: Option<DefaultValue>,
+    comments: Comments,
+    deprecated: bool,
+    position: Option<(i32, i32)>,
 }
 
 impl Field {
     pub fn new(
         syntax: &Syntax,
+        scope: &str,
         field_descriptor_proto: &FieldDescriptorProto,
+        map_entries: &BTreeMap<String, DescriptorProto>,
+        comments: Comments,
+        position: Option<(i32, i32)>,
     ) -> Result<Self, PrutoipaBuildError> {
-        let name = field_descriptor_proto
-            .name
+        let name =
+            field_descriptor_proto
+                .name
+                .clone()
+                .ok_or_else(|| PrutoipaBuildError::MissingName {
+                    location: ErrorLocation::new(scope.to_string(), position).into(),
+                    kind: "field",
+                })?;
+        let full_name = format!("{scope}.{name}")
+            .trim_start_matches('.')
+            .to_string();
+        let error_location = ErrorLocation::new(full_name.clone(), position);
+
+        let json_name = field_descriptor_proto
+            .json_name
             .clone()
-            .ok_or(PrutoipaBuildError::InvalidData(
-                "Expected field to have name".to_string(),
-            ))?;
-        let field_type = Self::get_type(field_descriptor_proto)?;
-        let field_modifier = Self::get_modifier(syntax, field_descriptor_proto, &field_type)?;
+            .unwrap_or_else(|| to_json_name(&name));
+        let field_type = Self::get_type(scope, field_descriptor_proto, map_entries)
+            .map_err(|err| err.at(&error_location))?;
+        let field_modifier = Self::get_modifier(syntax, field_descriptor_proto, &field_type)
+            .map_err(|err| err.at(&error_location))?;
+        let field_presence = Self::get_presence(syntax, field_descriptor_proto);
+        let default_value = Self::parse_default_value(field_descriptor_proto, &field_type)
+            .map_err(|err| err.at(&error_location))?;
+        let implicit_default_value =
+            Self::get_implicit_default_value(syntax, field_descriptor_proto, &field_type);
 
         Ok(Self {
+            full_name,
             name,
+            json_name,
             field_modifier,
+            field_presence,
             field_type,
+            default_value,
+            implicit_default_value,
+            comments,
+            deprecated: field_descriptor_proto
+                .options
+                .as_ref()
+                .is_some_and(|options| options.deprecated()),
+            position,
         })
     }
 
     //
     pub fn get_name(&self) -> String {
-        self.name.to_snake_case()
+        self.name.clone()
+    }
+
+    /// Returns the name of the field qualified by its package and message, like
+    /// `package.Message.field`.
+    pub fn get_full_name(&self) -> String {
+        self.full_name.clone()
+    }
+
+    pub fn get_json_name(&self) -> String {
+        self.json_name.clone()
     }
 
     pub fn get_field_modifier(&self) -> FieldModifier {
-        self.field_modifier.clone()
+        self.field_modifier
+    }
+
+    pub fn get_field_presence(&self) -> FieldPresence {
+        self.field_presence
     }
 
     pub fn get_field_type(&self) -> FieldType {
         self.field_type.clone()
     }
 
+    /// Returns the default value of the field, falling back to the zero value of fields with
+    /// implicit presence if `implicit` is set.
+    pub fn get_default_value(&self, implicit: bool) -> Option<DefaultValue> {
+        match implicit {
+            true => self
+                .default_value
+                .clone()
+                .or_else(|| self.implicit_default_value.clone()),
+            false => self.default_value.clone(),
+        }
+    }
+
+    pub fn get_comments(&self) -> Comments {
+        self.comments.clone()
+    }
+
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated
+    }
+
+    pub fn get_error_location(&self) -> ErrorLocation {
+        ErrorLocation::new(self.full_name.clone(), self.position)
+    }
+
+    //
+    pub fn resolve(self, resolver: &Resolver) -> Result<Self, PrutoipaBuildError> {
+        let error_location = self.get_error_location();
+        let field_type = self
+            .field_type
+            .resolve(resolver)
+            .map_err(|err| err.at(&error_location))?;
+
+        // Enum defaults are known by name or number, the other one comes from the enum
+        let resolve_default_value =
+            |default_value: Option<DefaultValue>| match (default_value, &field_type) {
+                (
+                    Some(default_value @ (DefaultValue::EnumName(_) | DefaultValue::EnumNumber(_))),
+                    FieldType::Enum {
+                        package,
+                        descriptor,
+                    },
+                ) => resolver
+                    .resolve_enum_value(package, descriptor, &default_value)
+                    .map(Some)
+                    .map_err(|err| err.at(&error_location)),
+                (default_value, _) => Ok(default_value),
+            };
+
+        Ok(Self {
+            default_value: resolve_default_value(self.default_value)?,
+            implicit_default_value: resolve_default_value(self.implicit_default_value)?,
+            field_type,
+            ..self
+        })
+    }
+
     //
-    fn get_type(field: &FieldDescriptorProto) -> Result<FieldType, PrutoipaBuildError> {
+    fn get_type(
+        scope: &str,
+        field: &FieldDescriptorProto,
+        map_entries: &BTreeMap<String, DescriptorProto>,
+    ) -> Result<FieldType, PrutoipaBuildError> {
         match field.type_name.as_ref() {
-            Some(type_name) => {
-                let splitted_type_name = type_name.split(".").collect::<Vec<&str>>();
-                if splitted_type_name.len() == 3 {
-                    Ok(FieldType::Object {
-                        package: splitted_type_name[1].to_string(),
-                        descriptor: splitted_type_name[2].to_string(),
-                    })
-                } else {
-                    Err(PrutoipaBuildError::InvalidData(format!(
-                        "The object {type_name} is not valid."
-                    )))
-                }
+            Some(type_name) if map_entries.contains_key(type_name) => {
+                Self::get_map_type(scope, type_name, map_entries)
             }
+            Some(type_name) => Ok(Self::get_object_type(scope, field, type_name)),
             None => {
-                let prost_type = Type::from_i32(
-                    field
-                        .r#type
-                        .ok_or(PrutoipaBuildError::InvalidData("Expected type".to_string()))?,
-                )
-                .ok_or(PrutoipaBuildError::InvalidData(
-                    "Expected valid type.".to_string(),
-                ))?;
+                let prost_type = Type::from_i32(field.r#type.ok_or_else(|| {
+                    PrutoipaBuildError::InvalidField {
+                        location: ErrorLocation::default().into(),
+                        reason: "expected type".to_string(),
+                    }
+                })?)
+                .ok_or_else(|| PrutoipaBuildError::InvalidField {
+                    location: ErrorLocation::default().into(),
+                    reason: format!(
+                        "expected valid type, found {}",
+                        field.r#type.unwrap_or_default()
+                    ),
+                })?;
 
                 let scalar = match prost_type {
                     Type::Double => ScalarType::F64,
@@ -83,7 +207,12 @@ impl Field {
                     Type::Bool => ScalarType::Bool,
                     Type::String => ScalarType::String,
                     Type::Bytes => ScalarType::Bytes,
-                    Type::Message | Type::Enum | Type::Group => panic!("no type name specified"),
+                    Type::Message | Type::Enum | Type::Group => {
+                        return Err(PrutoipaBuildError::InvalidField {
+                            location: ErrorLocation::default().into(),
+                            reason: format!("expected type name for type {prost_type:?}"),
+                        })
+                    }
                 };
 
                 Ok(FieldType::Scalar(scalar))
@@ -91,38 +220,155 @@ impl Field {
         }
     }
 
+    fn get_object_type(scope: &str, field: &FieldDescriptorProto, type_name: &str) -> FieldType {
+        let kind = match Type::from_i32(field.r#type.unwrap_or_default()) {
+            Some(Type::Message | Type::Group) => Some(DescriptorKind::Message),
+            Some(Type::Enum) => Some(DescriptorKind::Enum),
+            _ => None,
+        };
+
+        FieldType::Unresolved {
+            type_name: type_name.to_string(),
+            scope: scope.to_string(),
+            kind,
+        }
+    }
+
+    fn get_map_type(
+        scope: &str,
+        type_name: &str,
+        map_entries: &BTreeMap<String, DescriptorProto>,
+    ) -> Result<FieldType, PrutoipaBuildError> {
+        let get_entry_field = |number: i32| {
+            map_entries
+                .get(type_name)
+                .and_then(|map_entry| map_entry.field.iter().find(|f| f.number() == number))
+                .ok_or_else(|| PrutoipaBuildError::InvalidField {
+                    location: ErrorLocation::default().into(),
+                    reason: format!("expected map entry '{type_name}' to have field {number}"),
+                })
+        };
+
+        // Entries can not nest other maps, so they are not looked up again
+        let key = match Self::get_type(scope, get_entry_field(1)?, &BTreeMap::new())? {
+            FieldType::Scalar(scalar_type) => scalar_type,
+            _ => {
+                return Err(PrutoipaBuildError::InvalidField {
+                    location: ErrorLocation::default().into(),
+                    reason: format!("expected map entry '{type_name}' to have scalar key"),
+                })
+            }
+        };
+        let value = Self::get_type(scope, get_entry_field(2)?, &BTreeMap::new())?;
+
+        Ok(FieldType::Map {
+            key,
+            value: Box::new(value),
+        })
+    }
+
+    fn parse_default_value(
+        field: &FieldDescriptorProto,
+        field_type: &FieldType,
+    ) -> Result<Option<DefaultValue>, PrutoipaBuildError> {
+        match (field.default_value.as_deref(), field_type) {
+            (None, _) => Ok(None),
+            (Some(default_value), FieldType::Scalar(scalar_type)) => {
+                DefaultValue::new(*scalar_type, default_value).map(Some)
+            }
+            (
+                Some(default_value),
+                FieldType::Unresolved {
+                    kind: Some(DescriptorKind::Enum),
+                    ..
+                },
+            ) => Ok(Some(DefaultValue::EnumName(default_value.to_string()))),
+            (Some(default_value), _) => Err(PrutoipaBuildError::InvalidDefaultValue {
+                location: ErrorLocation::default().into(),
+                default_value: default_value.to_string(),
+                reason: "only scalar and enum fields have default values".to_string(),
+            }),
+        }
+    }
+
+    /// Returns the zero value of singular proto3 fields without explicit presence.
+    fn get_implicit_default_value(
+        syntax: &Syntax,
+        field: &FieldDescriptorProto,
+        field_type: &FieldType,
+    ) -> Option<DefaultValue> {
+        let implicit_presence = *syntax == Syntax::Proto3
+            && field.label() == Label::Optional
+            && !field.proto3_optional()
+            && field.oneof_index.is_none();
+
+        match field_type {
+            FieldType::Scalar(scalar_type) if implicit_presence => {
+                Some(DefaultValue::get_zero(*scalar_type))
+            }
+            FieldType::Unresolved {
+                kind: Some(DescriptorKind::Enum),
+                ..
+            } if implicit_presence => Some(DefaultValue::EnumNumber(0)),
+            _ => None,
+        }
+    }
+
+    fn get_presence(syntax: &Syntax, field: &FieldDescriptorProto) -> FieldPresence {
+        match field.label() {
+            Label::Required => FieldPresence::Required,
+            Label::Optional if field.proto3_optional() => FieldPresence::Optional,
+            Label::Optional if *syntax == Syntax::Proto2 && field.oneof_index.is_none() => {
+                FieldPresence::Optional
+            }
+            _ => FieldPresence::Unspecified,
+        }
+    }
+
     fn get_modifier(
         syntax: &Syntax,
         field: &FieldDescriptorProto,
         field_type: &FieldType,
     ) -> Result<FieldModifier, PrutoipaBuildError> {
-        let label = Label::from_i32(field.label.ok_or(PrutoipaBuildError::InvalidData(
-            "Expcted label.".to_string(),
-        ))?)
-        .ok_or(PrutoipaBuildError::InvalidData(
-            "Exptected valid label.".to_string(),
-        ))?;
-
-        if field.proto3_optional.unwrap_or(false) {
-            assert_eq!(label, Label::Optional);
-            Ok(FieldModifier::Optional)
-        } else if field.oneof_index.is_some() {
-            assert_eq!(label, Label::Optional);
-            Ok(FieldModifier::Optional)
+        let label =
+            Label::from_i32(
+                field
+                    .label
+                    .ok_or_else(|| PrutoipaBuildError::InvalidField {
+                        location: ErrorLocation::default().into(),
+                        reason: "expected label".to_string(),
+                    })?,
+            )
+            .ok_or_else(|| PrutoipaBuildError::InvalidField {
+                location: ErrorLocation::default().into(),
+                reason: format!(
+                    "expected valid label, found {}",
+                    field.label.unwrap_or_default()
+                ),
+            })?;
+
+        if field.proto3_optional.unwrap_or(false) || field.oneof_index.is_some() {
+            match label {
+                Label::Optional => Ok(FieldModifier::Optional),
+                _ => Err(PrutoipaBuildError::InvalidField {
+                    location: ErrorLocation::default().into(),
+                    reason: format!("expected one of member to be optional, found {label:?}"),
+                }),
+            }
         } else {
             match label {
                 Label::Optional => match syntax {
                     Syntax::Proto2 => Ok(FieldModifier::Optional),
                     Syntax::Proto3 => match field_type {
                         FieldType::Scalar(_) => Ok(FieldModifier::Required),
-                        FieldType::Object {
-                            package: _,
-                            descriptor: _,
-                        } => Ok(FieldModifier::Optional),
+                        _ => Ok(FieldModifier::Optional),
                     },
                 },
                 Label::Required => Ok(FieldModifier::Required),
-                Label::Repeated => Ok(FieldModifier::Repeated),
+                Label::Repeated => match field_type {
+                    FieldType::Map { key: _, value: _ } => Ok(FieldModifier::Optional),
+                    _ => Ok(FieldModifier::Repeated),
+                },
             }
         }
     }
@@ -131,7 +377,72 @@ impl Field {
 #[derive(Debug, Clone, PartialEq)]
 pub enum FieldType {
     Scalar(ScalarType),
-    Object { package: String, descriptor: String },
+    Object {
+        package: String,
+        descriptor: String,
+    },
+    Enum {
+        package: String,
+        descriptor: String,
+    },
+    Map {
+        key: ScalarType,
+        value: Box<FieldType>,
+    },
+    WellKnown(WellKnownType),
+    Unresolved {
+        type_name: String,
+        scope: String,
+        kind: Option<DescriptorKind>,
+    },
+}
+
+impl FieldType {
+    pub fn resolve(self, resolver: &Resolver) -> Result<Self, PrutoipaBuildError> {
+        match self {
+            Self::Unresolved {
+                type_name,
+                scope,
+                kind,
+            } => {
+                let symbol = resolver.resolve(&type_name, &scope)?;
+
+                match kind {
+                    Some(kind) if kind != symbol.kind => Err(PrutoipaBuildError::UnexpectedType {
+                        location: ErrorLocation::default().into(),
+                        type_name,
+                        expected: match kind {
+                            DescriptorKind::Message => "a message",
+                            DescriptorKind::Enum => "an enum",
+                        },
+                    }),
+                    _ if symbol.package == WELL_KNOWN_PACKAGE => {
+                        WellKnownType::get(&symbol.descriptor)
+                            .map(Self::WellKnown)
+                            .ok_or(PrutoipaBuildError::UnsupportedType {
+                                location: ErrorLocation::default().into(),
+                                type_name,
+                            })
+                    }
+                    _ => match symbol.kind {
+                        DescriptorKind::Message => Ok(Self::Object {
+                            package: symbol.package,
+                            descriptor: symbol.descriptor,
+                        }),
+                        DescriptorKind::Enum => Ok(Self::Enum {
+                            package: symbol.package,
+                            descriptor: symbol.descriptor,
+                        }),
+                    },
+                }
+            }
+            Self::Map { key, value } => Ok(Self::Map {
+                key,
+                value: Box::new(value.resolve(resolver)?),
+            }),
+            field_type => Ok(field_type),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -148,25 +459,42 @@ pub enum ScalarType {
 }
 
 impl ScalarType {
-    pub fn get_utoipa_type(&self) -> &'static str {
+    pub fn get_utoipa_type(&self) -> SchemaType {
+        match self {
+            Self::String | Self::Bytes => SchemaType::String,
+            Self::I32 | Self::I64 | Self::U32 | Self::U64 => SchemaType::Integer,
+            Self::F64 | Self::F32 => SchemaType::Number,
+            Self::Bool => SchemaType::Boolean,
+        }
+    }
+
+    pub fn get_map_key_description(&self) -> Option<&'static str> {
+        match self {
+            Self::I32 | Self::I64 | Self::U32 | Self::U64 => {
+                Some("Keys are integers encoded as strings.")
+            }
+            Self::Bool => Some("Keys are booleans encoded as strings."),
+            _ => None,
+        }
+    }
+
+    /// Returns the pattern of the string proto3 JSON encodes the 64-bit integers as.
+    pub fn get_string_pattern(&self) -> Option<&'static str> {
         match self {
-            Self::String => "String",
-            Self::I32 | Self::I64 | Self::U32 | Self::U64 => "Integer",
-            Self::F64 | Self::F32 => "Number",
-            Self::Bool => "Boolean",
-            _ => todo!(
-                "{}",
-                PrutoipaBuildError::NotImplementedYet("Bytes".to_string())
-            ),
+            Self::I64 => Some("^-?[0-9]+$"),
+            Self::U64 => Some("^[0-9]+$"),
+            _ => None,
         }
     }
 
-    pub fn get_utoipa_format(&self) -> Option<&'static str> {
+    pub fn get_utoipa_format(&self) -> Option<KnownFormat> {
         match self {
-            Self::I32 | Self::U32 => Some("Int32"),
-            Self::I64 | Self::U64 => Some("Int64"),
-            Self::F32 => Some("Float"),
-            Self::F64 => Some("Double"),
+            Self::I32 | Self::U32 => Some(KnownFormat::Int32),
+            Self::I64 | Self::U64 => Some(KnownFormat::Int64),
+            Self::F32 => Some(KnownFormat::Float),
+            Self::F64 => Some(KnownFormat::Double),
+            // Proto3 JSON encodes bytes as base64 strings
+            Self::Bytes => Some(KnownFormat::Byte),
             _ => None,
         }
     }
@@ -178,3 +506,137 @@ pub enum FieldModifier {
     Optional,
     Repeated,
 }
+
+/// Presence a field is declared with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldPresence {
+    /// Proto2 `required` fields.
+    Required,
+    /// Fields declared `optional`, either proto2 fields or proto3 fields with explicit presence.
+    Optional,
+    /// Proto3 singular fields, repeated fields, maps and one-of members.
+    Unspecified,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_bytes_field(syntax: &Syntax, label: Label) -> Field {
+        let field_descriptor_proto = FieldDescriptorProto {
+            r#type: Some(Type::Bytes.into()),
+            name: Some("payload".to_string()),
+            number: Some(1),
+            label: Some(label.into()),
+            ..Default::default()
+        };
+
+        Field::new(
+            syntax,
+            "people.Person",
+            &field_descriptor_proto,
+            &BTreeMap::new(),
+            Comments::default(),
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn bytes_field() {
+        let field = get_bytes_field(&Syntax::Proto3, Label::Optional);
+
+        assert_eq!(field.get_full_name(), "people.Person.payload");
+        assert_eq!(field.get_field_modifier(), FieldModifier::Required);
+        assert_eq!(field.get_field_presence(), FieldPresence::Unspecified);
+        assert_eq!(field.get_field_type(), FieldType::Scalar(ScalarType::Bytes));
+        assert_eq!(ScalarType::Bytes.get_utoipa_type(), SchemaType::String);
+        assert_eq!(
+            ScalarType::Bytes.get_utoipa_format(),
+            Some(KnownFormat::Byte)
+        );
+
+        let field = get_bytes_field(&Syntax::Proto3, Label::Repeated);
+
+        assert_eq!(field.get_field_modifier(), FieldModifier::Repeated);
+        assert_eq!(field.get_field_type(), FieldType::Scalar(ScalarType::Bytes));
+
+        let field = get_bytes_field(&Syntax::Proto2, Label::Optional);
+
+        assert_eq!(field.get_field_modifier(), FieldModifier::Optional);
+        assert_eq!(field.get_field_presence(), FieldPresence::Optional);
+
+        let field = get_bytes_field(&Syntax::Proto2, Label::Required);
+
+        assert_eq!(field.get_field_modifier(), FieldModifier::Required);
+        assert_eq!(field.get_field_presence(), FieldPresence::Required);
+        assert_eq!(field.get_field_type(), FieldType::Scalar(ScalarType::Bytes));
+    }
+
+    #[test]
+    fn proto3_optional_field() {
+        let field_descriptor_proto = FieldDescriptorProto {
+            r#type: Some(Type::Int32.into()),
+            name: Some("age".to_string()),
+            number: Some(1),
+            label: Some(Label::Optional.into()),
+            oneof_index: Some(0),
+            proto3_optional: Some(true),
+            ..Default::default()
+        };
+
+        let field = Field::new(
+            &Syntax::Proto3,
+            "people.Person",
+            &field_descriptor_proto,
+            &BTreeMap::new(),
+            Comments::default(),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(field.get_field_modifier(), FieldModifier::Optional);
+        assert_eq!(field.get_field_presence(), FieldPresence::Optional);
+        assert_eq!(field.get_default_value(true), None);
+    }
+
+    #[test]
+    fn string_pattern() {
+        assert_eq!(ScalarType::I64.get_string_pattern(), Some("^-?[0-9]+$"));
+        assert_eq!(ScalarType::U64.get_string_pattern(), Some("^[0-9]+$"));
+        assert_eq!(ScalarType::I32.get_string_pattern(), None);
+        assert_eq!(ScalarType::U32.get_string_pattern(), None);
+    }
+
+    #[test]
+    fn json_name() {
+        let get_field = |json_name: Option<&str>| {
+            let field_descriptor_proto = FieldDescriptorProto {
+                r#type: Some(Type::String.into()),
+                name: Some("first_name".to_string()),
+                json_name: json_name.map(str::to_string),
+                number: Some(1),
+                label: Some(Label::Optional.into()),
+                ..Default::default()
+            };
+
+            Field::new(
+                &Syntax::Proto3,
+                "people.Person",
+                &field_descriptor_proto,
+                &BTreeMap::new(),
+                Comments::default(),
+                None,
+            )
+            .unwrap()
+        };
+
+        let field = get_field(None);
+        assert_eq!(field.get_name(), "first_name");
+        assert_eq!(field.get_json_name(), "firstName");
+
+        let field = get_field(Some("givenName"));
+        assert_eq!(field.get_name(), "first_name");
+        assert_eq!(field.get_json_name(), "givenName");
+    }
+}
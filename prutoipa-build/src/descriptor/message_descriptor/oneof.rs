@@ -0,0 +1,51 @@
+use crate::{
+    descriptor::{comments::Comments, message_descriptor::field::Field},
+    error::PrutoipaBuildError,
+    resolver::Resolver,
+};
+
+#[derive(Debug, Clone)]
+pub struct OneOf {
+    name: String,
+    fields: Vec<Field>,
+    comments: Comments,
+}
+
+impl OneOf {
+    pub fn new(name: String, comments: Comments) -> Self {
+        Self {
+            name,
+            fields: Vec::new(),
+            comments,
+        }
+    }
+
+    //
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn get_fields(&self) -> Vec<Field> {
+        self.fields.clone()
+    }
+
+    pub fn get_comments(&self) -> Comments {
+        self.comments.clone()
+    }
+
+    //
+    pub fn push_field(&mut self, field: Field) {
+        self.fields.push(field);
+    }
+
+    pub fn resolve(self, resolver: &Resolver) -> Result<Self, PrutoipaBuildError> {
+        Ok(Self {
+            fields: self
+                .fields
+                .into_iter()
+                .map(|field| field.resolve(resolver))
+                .collect::<Result<Vec<Field>, PrutoipaBuildError>>()?,
+            ..self
+        })
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{descriptor::message_descriptor::field::ScalarType, resolver::DescriptorKind};
+
+pub const WELL_KNOWN_PACKAGE: &str = "google.protobuf";
+
+/// Types of `google.protobuf` with a special proto3 JSON mapping.
+///
+/// `prost` maps them to `prost_types`, so their schemas are inlined instead of referenced.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WellKnownType {
+    Any,
+    Duration,
+    Empty,
+    FieldMask,
+    ListValue,
+    NullValue,
+    Struct,
+    Timestamp,
+    Value,
+    Wrapper(ScalarType),
+}
+
+impl WellKnownType {
+    pub fn get(descriptor: &str) -> Option<Self> {
+        match descriptor {
+            "Any" => Some(Self::Any),
+            "Duration" => Some(Self::Duration),
+            "Empty" => Some(Self::Empty),
+            "FieldMask" => Some(Self::FieldMask),
+            "ListValue" => Some(Self::ListValue),
+            "NullValue" => Some(Self::NullValue),
+            "Struct" => Some(Self::Struct),
+            "Timestamp" => Some(Self::Timestamp),
+            "Value" => Some(Self::Value),
+            "DoubleValue" => Some(Self::Wrapper(ScalarType::F64)),
+            "FloatValue" => Some(Self::Wrapper(ScalarType::F32)),
+            "Int64Value" => Some(Self::Wrapper(ScalarType::I64)),
+            "UInt64Value" => Some(Self::Wrapper(ScalarType::U64)),
+            "Int32Value" => Some(Self::Wrapper(ScalarType::I32)),
+            "UInt32Value" => Some(Self::Wrapper(ScalarType::U32)),
+            "BoolValue" => Some(Self::Wrapper(ScalarType::Bool)),
+            "StringValue" => Some(Self::Wrapper(ScalarType::String)),
+            "BytesValue" => Some(Self::Wrapper(ScalarType::Bytes)),
+            _ => None,
+        }
+    }
+
+    /// Returns the name and kind of every well-known type, so they can be resolved even when
+    /// their files are not registered.
+    pub fn get_descriptors() -> Vec<(&'static str, DescriptorKind)> {
+        vec![
+            ("Any", DescriptorKind::Message),
+            ("Duration", DescriptorKind::Message),
+            ("Empty", DescriptorKind::Message),
+            ("FieldMask", DescriptorKind::Message),
+            ("ListValue", DescriptorKind::Message),
+            ("NullValue", DescriptorKind::Enum),
+            ("Struct", DescriptorKind::Message),
+            ("Timestamp", DescriptorKind::Message),
+            ("Value", DescriptorKind::Message),
+            ("DoubleValue", DescriptorKind::Message),
+            ("FloatValue", DescriptorKind::Message),
+            ("Int64Value", DescriptorKind::Message),
+            ("UInt64Value", DescriptorKind::Message),
+            ("Int32Value", DescriptorKind::Message),
+            ("UInt32Value", DescriptorKind::Message),
+            ("BoolValue", DescriptorKind::Message),
+            ("StringValue", DescriptorKind::Message),
+            ("BytesValue", DescriptorKind::Message),
+        ]
+    }
+}
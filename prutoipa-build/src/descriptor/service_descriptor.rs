@@ -0,0 +1,192 @@
+pub mod http_rule;
+
+use prost_types::ServiceDescriptorProto;
+
+use crate::{
+    descriptor::{
+        comments::{get_child_path, Comments, FileComments, SERVICE_METHOD},
+        message_descriptor::field::FieldType,
+        service_descriptor::http_rule::{AnnotatedService, HttpBinding},
+    },
+    error::{ErrorLocation, PrutoipaBuildError},
+    resolver::{DescriptorKind, Resolver},
+};
+
+#[derive(Debug, Clone)]
+pub struct ServiceDescriptor {
+    methods: Vec<Method>,
+    comments: Comments,
+    deprecated: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Method {
+    name: String,
+    full_name: String,
+    input_type: FieldType,
+    output_type: FieldType,
+    http_bindings: Vec<HttpBinding>,
+    comments: Comments,
+    deprecated: bool,
+    position: Option<(i32, i32)>,
+}
+
+impl ServiceDescriptor {
+    /// Creates the service, `annotated` holds the `google.api.http` annotations of its methods
+    /// if the descriptor set they come from was encoded.
+    pub fn new(
+        package_name: String,
+        path: String,
+        descriptor: ServiceDescriptorProto,
+        annotated: Option<AnnotatedService>,
+        file_comments: &FileComments,
+        location: &[i32],
+    ) -> Result<Self, PrutoipaBuildError> {
+        let scope = format!("{package_name}.{path}");
+        let annotated_methods = annotated.map(|service| service.method).unwrap_or_default();
+
+        let methods = descriptor
+            .method
+            .iter()
+            .enumerate()
+            .map(|(idx, method_descriptor_proto)| {
+                let method_location = get_child_path(location, SERVICE_METHOD, idx);
+                let position = file_comments.get_position(&method_location);
+                let name = method_descriptor_proto.name.clone().ok_or_else(|| {
+                    PrutoipaBuildError::MissingName {
+                        location: ErrorLocation::new(scope.clone(), position).into(),
+                        kind: "method",
+                    }
+                })?;
+                let full_name = format!("{scope}.{name}");
+                let error_location = ErrorLocation::new(full_name.clone(), position);
+
+                let get_type = |type_name: Option<&String>, kind: &'static str| {
+                    type_name
+                        .map(|type_name| FieldType::Unresolved {
+                            type_name: type_name.clone(),
+                            scope: package_name.clone(),
+                            kind: Some(DescriptorKind::Message),
+                        })
+                        .ok_or_else(|| PrutoipaBuildError::MissingName {
+                            location: error_location.clone().into(),
+                            kind,
+                        })
+                };
+                let http_bindings = annotated_methods
+                    .get(idx)
+                    .and_then(|method| method.options.as_ref())
+                    .and_then(|options| options.http.as_ref())
+                    .map(|http_rule| http_rule.get_bindings())
+                    .transpose()
+                    .map_err(|reason| PrutoipaBuildError::InvalidHttpRule {
+                        location: error_location.clone().into(),
+                        reason,
+                    })?
+                    .unwrap_or_default();
+
+                Ok(Method {
+                    input_type: get_type(
+                        method_descriptor_proto.input_type.as_ref(),
+                        "input type",
+                    )?,
+                    output_type: get_type(
+                        method_descriptor_proto.output_type.as_ref(),
+                        "output type",
+                    )?,
+                    name,
+                    full_name,
+                    http_bindings,
+                    comments: file_comments.get_comments(&method_location),
+                    deprecated: method_descriptor_proto
+                        .options
+                        .as_ref()
+                        .is_some_and(|options| options.deprecated()),
+                    position,
+                })
+            })
+            .collect::<Result<Vec<Method>, PrutoipaBuildError>>()?;
+
+        Ok(Self {
+            methods,
+            comments: file_comments.get_comments(location),
+            deprecated: descriptor
+                .options
+                .as_ref()
+                .is_some_and(|options| options.deprecated()),
+        })
+    }
+
+    //
+    pub fn get_methods(&self) -> Vec<Method> {
+        self.methods.clone()
+    }
+
+    pub fn get_comments(&self) -> Comments {
+        self.comments.clone()
+    }
+
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated
+    }
+
+    //
+    pub fn resolve(self, resolver: &Resolver) -> Result<Self, PrutoipaBuildError> {
+        Ok(Self {
+            methods: self
+                .methods
+                .into_iter()
+                .map(|method| method.resolve(resolver))
+                .collect::<Result<Vec<Method>, PrutoipaBuildError>>()?,
+            ..self
+        })
+    }
+}
+
+impl Method {
+    //
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn get_input_type(&self) -> FieldType {
+        self.input_type.clone()
+    }
+
+    pub fn get_output_type(&self) -> FieldType {
+        self.output_type.clone()
+    }
+
+    pub fn get_http_bindings(&self) -> Vec<HttpBinding> {
+        self.http_bindings.clone()
+    }
+
+    pub fn get_comments(&self) -> Comments {
+        self.comments.clone()
+    }
+
+    pub fn is_deprecated(&self) -> bool {
+        self.deprecated
+    }
+
+    pub fn get_error_location(&self) -> ErrorLocation {
+        ErrorLocation::new(self.full_name.clone(), self.position)
+    }
+
+    //
+    pub fn resolve(self, resolver: &Resolver) -> Result<Self, PrutoipaBuildError> {
+        let error_location = self.get_error_location();
+
+        Ok(Self {
+            input_type: self
+                .input_type
+                .resolve(resolver)
+                .map_err(|err| err.at(&error_location))?,
+            output_type: self
+                .output_type
+                .resolve(resolver)
+                .map_err(|err| err.at(&error_location))?,
+            ..self
+        })
+    }
+}
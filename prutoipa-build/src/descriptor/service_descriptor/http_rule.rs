@@ -0,0 +1,315 @@
+//! `google.api.http` annotations. `prost_types` drops extensions of the options as unknown fields
+//! when decoding, so the encoded descriptor sets are decoded again keeping only the services and
+//! their annotations.
+
+/// Subset of `google.protobuf.FileDescriptorSet` keeping the annotations of the methods.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AnnotatedFileDescriptorSet {
+    #[prost(message, repeated, tag = "1")]
+    pub file: Vec<AnnotatedFile>,
+}
+
+/// Subset of `google.protobuf.compiler.CodeGeneratorRequest` keeping the annotations of the
+/// methods.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AnnotatedCodeGeneratorRequest {
+    #[prost(message, repeated, tag = "15")]
+    pub proto_file: Vec<AnnotatedFile>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AnnotatedFile {
+    #[prost(message, repeated, tag = "6")]
+    pub service: Vec<AnnotatedService>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AnnotatedService {
+    #[prost(string, optional, tag = "1")]
+    pub name: Option<String>,
+    #[prost(message, repeated, tag = "2")]
+    pub method: Vec<AnnotatedMethod>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AnnotatedMethod {
+    #[prost(string, optional, tag = "1")]
+    pub name: Option<String>,
+    #[prost(string, optional, tag = "2")]
+    pub input_type: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    pub output_type: Option<String>,
+    #[prost(message, optional, tag = "4")]
+    pub options: Option<AnnotatedMethodOptions>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AnnotatedMethodOptions {
+    #[prost(message, optional, tag = "72295728")]
+    pub http: Option<HttpRule>,
+}
+
+/// `google.api.HttpRule`, the pattern one-of is decoded as separate fields.
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct HttpRule {
+    #[prost(string, tag = "1")]
+    pub selector: String,
+    #[prost(string, optional, tag = "2")]
+    pub get: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    pub put: Option<String>,
+    #[prost(string, optional, tag = "4")]
+    pub post: Option<String>,
+    #[prost(string, optional, tag = "5")]
+    pub delete: Option<String>,
+    #[prost(string, optional, tag = "6")]
+    pub patch: Option<String>,
+    #[prost(string, tag = "7")]
+    pub body: String,
+    #[prost(message, optional, tag = "8")]
+    pub custom: Option<CustomHttpPattern>,
+    #[prost(message, repeated, tag = "11")]
+    pub additional_bindings: Vec<HttpRule>,
+    #[prost(string, tag = "12")]
+    pub response_body: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct CustomHttpPattern {
+    #[prost(string, tag = "1")]
+    pub kind: String,
+    #[prost(string, tag = "2")]
+    pub path: String,
+}
+
+/// HTTP methods of the bindings, named as `utoipa::openapi::path::PathItemType` variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpMethod {
+    Get,
+    Put,
+    Post,
+    Delete,
+    Patch,
+    Head,
+    Options,
+    Trace,
+    Connect,
+}
+
+/// Which part of the request or response message is sent as the HTTP body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HttpBody {
+    /// Nothing, every field not bound to the path is a query parameter.
+    None,
+    /// The whole message.
+    Message,
+    /// A single top-level field, every other field not bound to the path is a query parameter.
+    Field(String),
+}
+
+/// A single HTTP binding of a method.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpBinding {
+    pub method: HttpMethod,
+    /// OpenAPI path, variables are named by the field path they are bound to.
+    pub path: String,
+    /// Field paths of the request bound to the variables of the path, like `book.id`.
+    pub path_fields: Vec<String>,
+    pub body: HttpBody,
+    pub response_body: HttpBody,
+}
+
+impl HttpRule {
+    /// Returns the binding of the rule followed by its additional bindings. Additional bindings
+    /// can not be nested, so their own additional bindings are ignored.
+    pub fn get_bindings(&self) -> Result<Vec<HttpBinding>, String> {
+        std::iter::once(self)
+            .chain(self.additional_bindings.iter())
+            .map(HttpRule::get_binding)
+            .collect()
+    }
+
+    fn get_binding(&self) -> Result<HttpBinding, String> {
+        let (method, template) = match (
+            &self.get,
+            &self.put,
+            &self.post,
+            &self.delete,
+            &self.patch,
+            &self.custom,
+        ) {
+            (Some(template), ..) => (HttpMethod::Get, template.as_str()),
+            (_, Some(template), ..) => (HttpMethod::Put, template.as_str()),
+            (_, _, Some(template), ..) => (HttpMethod::Post, template.as_str()),
+            (_, _, _, Some(template), ..) => (HttpMethod::Delete, template.as_str()),
+            (_, _, _, _, Some(template), _) => (HttpMethod::Patch, template.as_str()),
+            (_, _, _, _, _, Some(custom)) => {
+                (get_custom_method(&custom.kind)?, custom.path.as_str())
+            }
+            _ => return Err("expected an HTTP method".to_string()),
+        };
+        let (path, path_fields) = parse_path_template(template)?;
+
+        Ok(HttpBinding {
+            method,
+            path,
+            path_fields,
+            body: get_body(&self.body),
+            response_body: match get_body(&self.response_body) {
+                HttpBody::Field(field) => HttpBody::Field(field),
+                _ => HttpBody::Message,
+            },
+        })
+    }
+}
+
+fn get_custom_method(kind: &str) -> Result<HttpMethod, String> {
+    match kind.to_ascii_uppercase().as_str() {
+        "GET" => Ok(HttpMethod::Get),
+        "PUT" => Ok(HttpMethod::Put),
+        "POST" => Ok(HttpMethod::Post),
+        "DELETE" => Ok(HttpMethod::Delete),
+        "PATCH" => Ok(HttpMethod::Patch),
+        "HEAD" => Ok(HttpMethod::Head),
+        "OPTIONS" => Ok(HttpMethod::Options),
+        "TRACE" => Ok(HttpMethod::Trace),
+        "CONNECT" => Ok(HttpMethod::Connect),
+        _ => Err(format!("unsupported HTTP method '{kind}'")),
+    }
+}
+
+fn get_body(body: &str) -> HttpBody {
+    match body {
+        "" => HttpBody::None,
+        "*" => HttpBody::Message,
+        field => HttpBody::Field(field.to_string()),
+    }
+}
+
+/// Converts a path template like `/v1/{name=shelves/*}/books` to an OpenAPI path like
+/// `/v1/{name}/books`, returning it with the field paths of its variables.
+fn parse_path_template(template: &str) -> Result<(String, Vec<String>), String> {
+    if !template.starts_with('/') {
+        return Err(format!("path template '{template}' must start with '/'"));
+    }
+
+    let mut path = String::new();
+    let mut path_fields = Vec::<String>::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .ok_or_else(|| format!("unclosed variable at path template '{template}'"))?;
+        let variable = &rest[start + 1..end];
+        let field_path = variable.split('=').next().unwrap_or_default().trim();
+
+        let valid_field_path = field_path.split('.').all(|segment| {
+            !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        });
+        if !valid_field_path || variable.contains('{') {
+            return Err(format!(
+                "invalid variable '{variable}' at path template '{template}'"
+            ));
+        }
+
+        path.push_str(&rest[..start]);
+        path.push_str(&format!("{{{field_path}}}"));
+        path_fields.push(field_path.to_string());
+        rest = &rest[end + 1..];
+    }
+
+    if rest.contains('}') {
+        return Err(format!("unopened variable at path template '{template}'"));
+    }
+    path.push_str(rest);
+
+    Ok((path, path_fields))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_templates() {
+        assert_eq!(
+            parse_path_template("/v1/shelves/{shelf}/books/{book.id}:publish"),
+            Ok((
+                "/v1/shelves/{shelf}/books/{book.id}:publish".to_string(),
+                vec!["shelf".to_string(), "book.id".to_string()]
+            ))
+        );
+        assert_eq!(
+            parse_path_template("/v1/{name=shelves/*/books/**}"),
+            Ok(("/v1/{name}".to_string(), vec!["name".to_string()]))
+        );
+        assert_eq!(
+            parse_path_template("/v1/books"),
+            Ok(("/v1/books".to_string(), vec![]))
+        );
+        assert!(parse_path_template("v1/books").is_err());
+        assert!(parse_path_template("/v1/{name").is_err());
+        assert!(parse_path_template("/v1/name}").is_err());
+        assert!(parse_path_template("/v1/{na-me}").is_err());
+        assert!(parse_path_template("/v1/{book.}").is_err());
+    }
+
+    #[test]
+    fn http_rule_bindings() {
+        let rule = HttpRule {
+            post: Some("/v1/books".to_string()),
+            body: "book".to_string(),
+            additional_bindings: vec![
+                HttpRule {
+                    put: Some("/v1/books/{book.id}".to_string()),
+                    body: "*".to_string(),
+                    ..Default::default()
+                },
+                HttpRule {
+                    custom: Some(CustomHttpPattern {
+                        kind: "head".to_string(),
+                        path: "/v1/books".to_string(),
+                    }),
+                    response_body: "id".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let expected_bindings = vec![
+            HttpBinding {
+                method: HttpMethod::Post,
+                path: "/v1/books".to_string(),
+                path_fields: vec![],
+                body: HttpBody::Field("book".to_string()),
+                response_body: HttpBody::Message,
+            },
+            HttpBinding {
+                method: HttpMethod::Put,
+                path: "/v1/books/{book.id}".to_string(),
+                path_fields: vec!["book.id".to_string()],
+                body: HttpBody::Message,
+                response_body: HttpBody::Message,
+            },
+            HttpBinding {
+                method: HttpMethod::Head,
+                path: "/v1/books".to_string(),
+                path_fields: vec![],
+                body: HttpBody::None,
+                response_body: HttpBody::Field("id".to_string()),
+            },
+        ];
+
+        assert_eq!(rule.get_bindings(), Ok(expected_bindings));
+        assert_eq!(
+            HttpRule::default().get_bindings(),
+            Err("expected an HTTP method".to_string())
+        );
+    }
+}
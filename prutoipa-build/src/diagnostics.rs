@@ -0,0 +1,66 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{Display, Formatter},
+};
+
+use crate::{
+    descriptor::Descriptor, error::ErrorLocation, generator::GeneratorOptions, package::Package,
+};
+
+/// Construct of the descriptors that the generated code does not document faithfully.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+    pub location: ErrorLocation,
+    pub message: String,
+}
+
+impl Warning {
+    pub fn new(location: ErrorLocation, message: String) -> Self {
+        Self { location, message }
+    }
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}", self.message, self.location)
+    }
+}
+
+/// Warns about the presence overrides of fields that are not defined, which are never applied.
+pub fn get_presence_override_warnings(
+    packages: &BTreeMap<String, Package>,
+    options: &GeneratorOptions,
+) -> Vec<Warning> {
+    let field_names = packages
+        .values()
+        .flat_map(|package| package.get_descriptors().into_values())
+        .filter_map(|descriptor| match descriptor {
+            Descriptor::Message(message) => Some(message),
+            Descriptor::Enum(_) => None,
+        })
+        .flat_map(|message| {
+            message
+                .get_oneofs()
+                .into_iter()
+                .flat_map(|oneof| oneof.get_fields())
+                .chain(message.get_fields())
+                .map(|field| field.get_full_name())
+                .collect::<Vec<String>>()
+        })
+        .collect::<BTreeSet<String>>();
+
+    options
+        .presence_overrides
+        .keys()
+        .filter(|field_name| !field_names.contains(*field_name))
+        .map(|field_name| {
+            Warning::new(
+                ErrorLocation {
+                    path: field_name.clone(),
+                    ..Default::default()
+                },
+                "Presence override of a field that is not defined".to_string(),
+            )
+        })
+        .collect()
+}
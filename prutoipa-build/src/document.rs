@@ -0,0 +1,273 @@
+pub mod paths;
+pub mod schema;
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+use utoipa::openapi::{tag::TagBuilder, ComponentsBuilder, InfoBuilder, OpenApi, OpenApiBuilder};
+
+use crate::{
+    descriptor::{enum_descriptor::EnumDescriptor, Descriptor},
+    document::{
+        paths::get_paths,
+        schema::{get_descriptor_schema, get_schema},
+    },
+    error::PrutoipaBuildError,
+    generator::{
+        enumeration::get_enum_schema, openapi::get_reachable_schemas, EnumRepresentation,
+        GeneratorOptions,
+    },
+    ident::to_schema_name,
+    package::Package,
+};
+
+/// Format of the standalone OpenAPI documents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DocumentFormat {
+    #[default]
+    Json,
+    Yaml,
+}
+
+/// OpenAPI version of the standalone OpenAPI documents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OpenApiVersion {
+    /// OpenAPI 3.0, as the generated code documents.
+    #[default]
+    V3_0,
+    /// OpenAPI 3.1, nullable schemas are documented with `null` types.
+    V3_1,
+}
+
+impl DocumentFormat {
+    pub fn get_extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Yaml => "yaml",
+        }
+    }
+}
+
+/// What the documents of a package depend on besides its descriptors.
+pub struct DocumentContext<'a> {
+    pub packages: &'a BTreeMap<String, Package>,
+    pub options: &'a GeneratorOptions,
+}
+
+/// Renders the OpenAPI document of a package, the same one its generated `openapi` function
+/// returns. Without configured info, the document is titled after the package.
+pub fn render_document(
+    package: &Package,
+    packages: &BTreeMap<String, Package>,
+    options: &GeneratorOptions,
+    format: DocumentFormat,
+    version: OpenApiVersion,
+) -> Result<String, PrutoipaBuildError> {
+    let context = DocumentContext { packages, options };
+    let openapi = get_openapi(package, &context)?;
+    let mut document = serde_json::to_value(openapi)
+        .map_err(|err| PrutoipaBuildError::InvalidDocument(err.to_string()))?;
+    add_deprecated_values(&mut document, &get_deprecated_enum_schemas(&context)?);
+
+    if version == OpenApiVersion::V3_1 {
+        document["openapi"] = Value::from("3.1.0");
+        to_openapi_3_1(&mut document);
+    }
+
+    match format {
+        DocumentFormat::Json => serde_json::to_string_pretty(&document)
+            .map(|document| format!("{document}\n"))
+            .map_err(|err| PrutoipaBuildError::InvalidDocument(err.to_string())),
+        DocumentFormat::Yaml => serde_norway::to_string(&document)
+            .map_err(|err| PrutoipaBuildError::InvalidDocument(err.to_string())),
+    }
+}
+
+/// Returns the schemas of the enums with deprecated values, along with these values.
+fn get_deprecated_enum_schemas(
+    context: &DocumentContext,
+) -> Result<Vec<(Value, Vec<String>)>, PrutoipaBuildError> {
+    context
+        .packages
+        .values()
+        .flat_map(|package| package.get_descriptors().into_values())
+        .filter_map(|descriptor| match descriptor {
+            Descriptor::Enum(enum_descriptor) => Some(enum_descriptor),
+            Descriptor::Message(_) => None,
+        })
+        .filter_map(|enum_descriptor| {
+            let deprecated_values = get_deprecated_values(&enum_descriptor, context.options);
+            if deprecated_values.is_empty() {
+                return None;
+            }
+
+            Some(
+                get_schema(context, get_enum_schema(enum_descriptor, context.options)).and_then(
+                    |schema| {
+                        serde_json::to_value(schema)
+                            .map(|schema| (schema, deprecated_values))
+                            .map_err(|err| PrutoipaBuildError::InvalidDocument(err.to_string()))
+                    },
+                ),
+            )
+        })
+        .collect()
+}
+
+/// Returns the deprecated values of an enum, as its schema lists them.
+fn get_deprecated_values(
+    enum_descriptor: &EnumDescriptor,
+    options: &GeneratorOptions,
+) -> Vec<String> {
+    enum_descriptor
+        .get_values()
+        .into_iter()
+        .filter(|enum_value| enum_value.deprecated)
+        .map(|enum_value| match options.enum_representation {
+            EnumRepresentation::String | EnumRepresentation::Either => enum_value.name,
+            EnumRepresentation::Integer => enum_value.number.to_string(),
+        })
+        .collect()
+}
+
+/// Lists the deprecated values of the enum schemas at their `x-deprecated-values` extension.
+/// Enum schemas are inlined, so they are found by value.
+fn add_deprecated_values(document: &mut Value, enum_schemas: &[(Value, Vec<String>)]) {
+    if let Some((_, deprecated_values)) = enum_schemas.iter().find(|(schema, _)| schema == document)
+    {
+        document["x-deprecated-values"] = Value::from(deprecated_values.clone());
+        return;
+    }
+
+    match document {
+        Value::Object(object) => object
+            .values_mut()
+            .for_each(|value| add_deprecated_values(value, enum_schemas)),
+        Value::Array(array) => array
+            .iter_mut()
+            .for_each(|value| add_deprecated_values(value, enum_schemas)),
+        _ => {}
+    }
+}
+
+fn get_openapi(
+    package: &Package,
+    context: &DocumentContext,
+) -> Result<OpenApi, PrutoipaBuildError> {
+    let package_name = package.get_name();
+    let options = context.options;
+
+    let (title, version) = match &options.openapi_info {
+        Some(info) => (info.title.clone(), info.version.clone()),
+        None => (package_name.clone(), "0.0.0".to_string()),
+    };
+    let description = package
+        .get_files()
+        .into_iter()
+        .filter_map(|file| {
+            file.get_comments()
+                .get_description(options.markdown_comments)
+        })
+        .collect::<Vec<String>>();
+    let info = InfoBuilder::new()
+        .title(title)
+        .version(version)
+        .description((!description.is_empty()).then(|| description.join("\n\n")))
+        .build();
+
+    let components = get_reachable_schemas(package, context.packages, options)
+        .into_iter()
+        .try_fold(
+            ComponentsBuilder::new(),
+            |components, (schema_package, schema_path)| {
+                Ok::<ComponentsBuilder, PrutoipaBuildError>(components.schema(
+                    to_schema_name(&schema_package, &schema_path),
+                    get_descriptor_schema(context, &schema_package, &schema_path)?,
+                ))
+            },
+        )?
+        .build();
+
+    let services = package.get_services();
+    let tags = services
+        .into_iter()
+        .map(|(service_name, service)| {
+            TagBuilder::new()
+                .name(service_name)
+                .description(
+                    service
+                        .get_comments()
+                        .get_description(options.markdown_comments),
+                )
+                .build()
+        })
+        .collect::<Vec<_>>();
+
+    Ok(OpenApiBuilder::new()
+        .info(info)
+        .paths(get_paths(package, context)?)
+        .components(Some(components))
+        .tags((!tags.is_empty()).then_some(tags))
+        .build())
+}
+
+/// Replaces the `nullable` keyword of OpenAPI 3.0, removed at OpenAPI 3.1, by `null` types at
+/// every schema of the document.
+fn to_openapi_3_1(value: &mut Value) {
+    match value {
+        Value::Object(object) => object
+            .iter_mut()
+            .for_each(|(key, value)| match key.as_str() {
+                "schema" => schema_to_openapi_3_1(value),
+                "schemas" => value
+                    .as_object_mut()
+                    .into_iter()
+                    .flat_map(|schemas| schemas.values_mut())
+                    .for_each(schema_to_openapi_3_1),
+                // Values, not schemas
+                "example" | "examples" => {}
+                _ => to_openapi_3_1(value),
+            }),
+        Value::Array(values) => values.iter_mut().for_each(to_openapi_3_1),
+        _ => {}
+    }
+}
+
+fn schema_to_openapi_3_1(schema: &mut Value) {
+    let Value::Object(object) = schema else {
+        return;
+    };
+
+    // Only the keywords holding schemas are walked, properties or default values named like
+    // keywords are kept as they are
+    object
+        .iter_mut()
+        .for_each(|(key, value)| match (key.as_str(), value) {
+            ("items" | "additionalProperties" | "not", value) => schema_to_openapi_3_1(value),
+            ("allOf" | "oneOf" | "anyOf", Value::Array(schemas)) => {
+                schemas.iter_mut().for_each(schema_to_openapi_3_1)
+            }
+            ("properties", Value::Object(properties)) => {
+                properties.values_mut().for_each(schema_to_openapi_3_1)
+            }
+            _ => {}
+        });
+
+    if object.get("nullable") == Some(&Value::Bool(true)) {
+        object.shift_remove("nullable");
+
+        if let Some(schema_type) = object.get_mut("type") {
+            *schema_type = Value::Array(vec![schema_type.take(), Value::from("null")]);
+        } else if !object.is_empty() {
+            // Schemas without type, like compositions, accept `null` as an alternative
+            let schema = std::mem::take(object);
+            object.insert(
+                "anyOf".to_string(),
+                Value::Array(vec![
+                    Value::Object(schema),
+                    serde_json::json!({ "type": "null" }),
+                ]),
+            );
+        }
+    }
+}
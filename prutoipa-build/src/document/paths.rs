@@ -0,0 +1,117 @@
+use utoipa::openapi::{
+    path::{self, OperationBuilder, ParameterBuilder, PathItemBuilder},
+    request_body::RequestBodyBuilder,
+    Content, ContentBuilder, Deprecated, PathItemType, Paths, PathsBuilder, Required,
+    ResponseBuilder,
+};
+
+use crate::{
+    descriptor::service_descriptor::http_rule::HttpMethod,
+    document::{schema::get_schema, DocumentContext},
+    error::PrutoipaBuildError,
+    generator::paths::{get_operations, Operation, Parameter, ParameterIn},
+    package::Package,
+    schema::Schema,
+};
+
+/// Returns the operations of the services of a package, transcoded from their `google.api.http`
+/// annotations.
+pub fn get_paths(
+    package: &Package,
+    context: &DocumentContext,
+) -> Result<Paths, PrutoipaBuildError> {
+    get_operations(package, context.packages, context.options)?
+        .into_iter()
+        .try_fold(PathsBuilder::new(), |paths, (path, path_operations)| {
+            let path_item = path_operations.into_iter().try_fold(
+                PathItemBuilder::new(),
+                |path_item, operation| {
+                    Ok::<PathItemBuilder, PrutoipaBuildError>(path_item.operation(
+                        get_path_item_type(operation.method),
+                        get_operation(context, operation)?,
+                    ))
+                },
+            )?;
+
+            Ok(paths.path(path, path_item.build()))
+        })
+        .map(PathsBuilder::build)
+}
+
+fn get_operation(
+    context: &DocumentContext,
+    operation: Operation,
+) -> Result<path::Operation, PrutoipaBuildError> {
+    let mut builder = OperationBuilder::new()
+        .operation_id(Some(operation.operation_id))
+        .tag(operation.tag)
+        .summary(operation.summary)
+        .description(operation.description);
+    if operation.deprecated {
+        builder = builder.deprecated(Some(Deprecated::True));
+    }
+
+    for parameter in operation.parameters {
+        builder = builder.parameter(get_parameter(context, parameter)?);
+    }
+
+    if let Some(request_body) = operation.request_body {
+        builder = builder.request_body(Some(
+            RequestBodyBuilder::new()
+                .content("application/json", get_content(context, request_body)?)
+                .required(Some(Required::True))
+                .build(),
+        ));
+    }
+
+    let mut response = ResponseBuilder::new().description("A successful response.");
+    if let Some(response_body) = operation.response_body {
+        response = response.content("application/json", get_content(context, response_body)?);
+    }
+
+    Ok(builder.response("200", response).build())
+}
+
+fn get_parameter(
+    context: &DocumentContext,
+    parameter: Parameter,
+) -> Result<path::Parameter, PrutoipaBuildError> {
+    let mut builder =
+        ParameterBuilder::new()
+            .name(parameter.name)
+            .parameter_in(match parameter.parameter_in {
+                ParameterIn::Path => path::ParameterIn::Path,
+                ParameterIn::Query => path::ParameterIn::Query,
+            });
+    if parameter.parameter_in == ParameterIn::Path {
+        builder = builder.required(Required::True);
+    }
+    if parameter.deprecated {
+        builder = builder.deprecated(Some(Deprecated::True));
+    }
+
+    Ok(builder
+        .description(parameter.description)
+        .schema(Some(get_schema(context, parameter.schema)?))
+        .build())
+}
+
+fn get_content(context: &DocumentContext, schema: Schema) -> Result<Content, PrutoipaBuildError> {
+    Ok(ContentBuilder::new()
+        .schema(get_schema(context, schema)?)
+        .build())
+}
+
+fn get_path_item_type(method: HttpMethod) -> PathItemType {
+    match method {
+        HttpMethod::Get => PathItemType::Get,
+        HttpMethod::Put => PathItemType::Put,
+        HttpMethod::Post => PathItemType::Post,
+        HttpMethod::Delete => PathItemType::Delete,
+        HttpMethod::Patch => PathItemType::Patch,
+        HttpMethod::Head => PathItemType::Head,
+        HttpMethod::Options => PathItemType::Options,
+        HttpMethod::Trace => PathItemType::Trace,
+        HttpMethod::Connect => PathItemType::Connect,
+    }
+}
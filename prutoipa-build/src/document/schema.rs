@@ -0,0 +1,216 @@
+use serde_json::Value;
+use utoipa::openapi::{
+    schema::{self as openapi_schema, Schema},
+    AllOfBuilder, Array, ArrayBuilder, Deprecated, KnownFormat, ObjectBuilder, OneOfBuilder, Ref,
+    RefOr, SchemaFormat, SchemaType,
+};
+
+use crate::{
+    descriptor::Descriptor,
+    document::DocumentContext,
+    error::{ErrorLocation, PrutoipaBuildError},
+    generator::{
+        enumeration::get_enum_schema,
+        message::{get_message_schema, get_nested_oneof_schema},
+    },
+    schema::{self, AdditionalProperties, ArraySchema, CompositeSchema, Literal, ObjectSchema},
+};
+
+/// Returns the schema of a descriptor, or of a one-of group at `Message.oneof_name` in
+/// `OneOfStyle::Nested`, as its `ToSchema` implementation does.
+pub fn get_descriptor_schema(
+    context: &DocumentContext,
+    package_name: &str,
+    path: &str,
+) -> Result<RefOr<Schema>, PrutoipaBuildError> {
+    let package = context.packages.get(package_name);
+    let full_name = format!("{package_name}.{path}");
+    let error_location = ErrorLocation {
+        file: package
+            .and_then(|package| package.get_descriptor_file(path))
+            .unwrap_or_default(),
+        package: package_name.to_string(),
+        path: full_name.clone(),
+        position: None,
+    };
+
+    let oneof = path
+        .rsplit_once('.')
+        .and_then(|(message_path, oneof_name)| {
+            match package.and_then(|package| package.get_descriptor(message_path)) {
+                Some(Descriptor::Message(message)) => message
+                    .get_oneofs()
+                    .into_iter()
+                    .find(|oneof| oneof.get_name() == oneof_name),
+                _ => None,
+            }
+        });
+
+    let schema = match (
+        package.and_then(|package| package.get_descriptor(path)),
+        oneof,
+    ) {
+        (Some(Descriptor::Message(message)), _) => get_message_schema(
+            package_name,
+            path,
+            &message,
+            context.packages,
+            context.options,
+        ),
+        (Some(Descriptor::Enum(enum_descriptor)), _) => {
+            Ok(get_enum_schema(enum_descriptor, context.options))
+        }
+        (None, Some(oneof)) => {
+            get_nested_oneof_schema(package_name, path, oneof, context.packages, context.options)
+        }
+        (None, None) => Err(PrutoipaBuildError::UnknownType {
+            location: ErrorLocation::default().into(),
+            type_name: full_name,
+        }),
+    };
+
+    schema
+        .and_then(|schema| get_schema(context, schema))
+        .map_err(|err| err.at(&error_location))
+}
+
+/// Builds a schema with the utoipa builders, as the generated code does, inlining the schemas of
+/// the descriptors it reaches.
+pub fn get_schema(
+    context: &DocumentContext,
+    schema: schema::Schema,
+) -> Result<RefOr<Schema>, PrutoipaBuildError> {
+    match schema {
+        schema::Schema::Object(object) => Ok(get_object(context, object)?.into()),
+        schema::Schema::Array(array) => Ok(get_array(context, array)?.into()),
+        schema::Schema::OneOf(one_of) => Ok(get_one_of(context, one_of)?.into()),
+        schema::Schema::AllOf(all_of) => Ok(get_all_of(context, all_of)?.into()),
+        schema::Schema::Descriptor { package, path } => {
+            get_descriptor_schema(context, &package, &path)
+        }
+        schema::Schema::Ref(schema_name) => Ok(RefOr::Ref(Ref::from_schema_name(schema_name))),
+    }
+}
+
+fn get_object(
+    context: &DocumentContext,
+    object: ObjectSchema,
+) -> Result<ObjectBuilder, PrutoipaBuildError> {
+    let mut builder = ObjectBuilder::new()
+        .format(
+            object
+                .format
+                .map(|known_format| SchemaFormat::KnownFormat(known_format.into())),
+        )
+        .pattern(object.pattern);
+    if let Some(schema_type) = object.schema_type {
+        builder = builder.schema_type(schema_type.into());
+    }
+    if let Some(enum_values) = object.enum_values {
+        builder = builder.enum_values(Some(enum_values));
+    }
+    for (property_name, property) in object.properties {
+        builder = builder.property(property_name, get_schema(context, property)?);
+    }
+    for property_name in object.required {
+        builder = builder.required(property_name);
+    }
+    builder = match object.additional_properties {
+        Some(AdditionalProperties::FreeForm) => builder
+            .additional_properties(Some(openapi_schema::AdditionalProperties::FreeForm(true))),
+        Some(AdditionalProperties::Schema(schema)) => {
+            builder.additional_properties(Some(get_schema(context, *schema)?))
+        }
+        None => builder,
+    };
+
+    Ok(builder
+        .nullable(object.nullable)
+        .default(object.default.map(get_value))
+        .deprecated(object.deprecated.then_some(Deprecated::True))
+        .description(object.description))
+}
+
+fn get_array(
+    context: &DocumentContext,
+    array: ArraySchema,
+) -> Result<ArrayBuilder, PrutoipaBuildError> {
+    Ok(
+        ArrayBuilder::from(Array::new(get_schema(context, *array.items)?))
+            .nullable(array.nullable)
+            .default(array.default.map(get_value))
+            .deprecated(array.deprecated.then_some(Deprecated::True))
+            .description(array.description),
+    )
+}
+
+fn get_one_of(
+    context: &DocumentContext,
+    one_of: CompositeSchema,
+) -> Result<OneOfBuilder, PrutoipaBuildError> {
+    let builder = one_of
+        .items
+        .into_iter()
+        .try_fold(OneOfBuilder::new(), |builder, item| {
+            Ok::<OneOfBuilder, PrutoipaBuildError>(builder.item(get_schema(context, item)?))
+        })?;
+
+    Ok(builder
+        .nullable(one_of.nullable)
+        .default(one_of.default.map(get_value))
+        .description(one_of.description))
+}
+
+fn get_all_of(
+    context: &DocumentContext,
+    all_of: CompositeSchema,
+) -> Result<AllOfBuilder, PrutoipaBuildError> {
+    let builder = all_of
+        .items
+        .into_iter()
+        .try_fold(AllOfBuilder::new(), |builder, item| {
+            Ok::<AllOfBuilder, PrutoipaBuildError>(builder.item(get_schema(context, item)?))
+        })?;
+
+    Ok(builder
+        .nullable(all_of.nullable)
+        .default(all_of.default.map(get_value))
+        .description(all_of.description))
+}
+
+fn get_value(literal: Literal) -> Value {
+    match literal {
+        Literal::Int32(value) => Value::from(value),
+        Literal::Int64(value) => Value::from(value),
+        Literal::UInt32(value) => Value::from(value),
+        Literal::UInt64(value) => Value::from(value),
+        Literal::Double(value) => Value::from(value),
+        Literal::Bool(value) => Value::from(value),
+        Literal::String(value) => Value::from(value),
+    }
+}
+
+impl From<schema::SchemaType> for SchemaType {
+    fn from(schema_type: schema::SchemaType) -> Self {
+        match schema_type {
+            schema::SchemaType::Value => Self::Value,
+            schema::SchemaType::String => Self::String,
+            schema::SchemaType::Integer => Self::Integer,
+            schema::SchemaType::Number => Self::Number,
+            schema::SchemaType::Boolean => Self::Boolean,
+        }
+    }
+}
+
+impl From<schema::KnownFormat> for KnownFormat {
+    fn from(known_format: schema::KnownFormat) -> Self {
+        match known_format {
+            schema::KnownFormat::Int32 => Self::Int32,
+            schema::KnownFormat::Int64 => Self::Int64,
+            schema::KnownFormat::Float => Self::Float,
+            schema::KnownFormat::Double => Self::Double,
+            schema::KnownFormat::Byte => Self::Byte,
+            schema::KnownFormat::DateTime => Self::DateTime,
+        }
+    }
+}
@@ -1,3 +1,7 @@
+use std::fmt::{Display, Formatter};
+
+use crate::diagnostics::Warning;
+
 #[derive(thiserror::Error, Debug)]
 pub enum PrutoipaBuildError {
     #[error("{0} is not implemented yet.")]
@@ -6,12 +10,173 @@ pub enum PrutoipaBuildError {
     #[error("Output dir not set.")]
     OutputDirNotSet,
 
-    #[error("Invalid data: {0}.")]
-    InvalidData(String),
-
     #[error("Invalid descriptor set.")]
     InvalidDescriptorSet(#[from] prost::DecodeError),
 
     #[error(transparent)]
     IoError(#[from] std::io::Error),
+
+    #[error("Expected {kind} name at {location}.")]
+    MissingName {
+        location: Box<ErrorLocation>,
+        kind: &'static str,
+    },
+
+    #[error("Unsupported syntax '{syntax}' at {location}.")]
+    UnsupportedSyntax {
+        location: Box<ErrorLocation>,
+        syntax: String,
+    },
+
+    #[error("Descriptor {location} is already defined at '{previous_file}'.")]
+    DuplicateDescriptor {
+        location: Box<ErrorLocation>,
+        previous_file: String,
+    },
+
+    #[error("Type '{type_name}' referenced at {location} is not defined.")]
+    UnknownType {
+        location: Box<ErrorLocation>,
+        type_name: String,
+    },
+
+    #[error("Type '{type_name}' referenced at {location} is not {expected}.")]
+    UnexpectedType {
+        location: Box<ErrorLocation>,
+        type_name: String,
+        expected: &'static str,
+    },
+
+    #[error("Type '{type_name}' referenced at {location} is not supported.")]
+    UnsupportedType {
+        location: Box<ErrorLocation>,
+        type_name: String,
+    },
+
+    #[error("Invalid field {location}: {reason}.")]
+    InvalidField {
+        location: Box<ErrorLocation>,
+        reason: String,
+    },
+
+    #[error("Invalid default value '{default_value}' at {location}: {reason}.")]
+    InvalidDefaultValue {
+        location: Box<ErrorLocation>,
+        default_value: String,
+        reason: String,
+    },
+
+    #[error("Invalid HTTP rule at {location}: {reason}.")]
+    InvalidHttpRule {
+        location: Box<ErrorLocation>,
+        reason: String,
+    },
+
+    #[error("Invalid OpenAPI document: {0}.")]
+    InvalidDocument(String),
+
+    #[error("Invalid proto files: {0}.")]
+    InvalidProtoFiles(String),
+
+    #[error("Invalid plugin parameter '{parameter}': {reason}.")]
+    InvalidParameter { parameter: String, reason: String },
+
+    #[error("{0} (strict mode).")]
+    StrictWarning(Box<Warning>),
+}
+
+/// Where the descriptor an error was found at is defined.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ErrorLocation {
+    pub file: String,
+    pub package: String,
+    /// Fully-qualified path of the descriptor, like `package.Message.field`.
+    pub path: String,
+    /// One-based line and column of the descriptor, only known if the descriptor set was
+    /// generated with `source_code_info`.
+    pub position: Option<(i32, i32)>,
+}
+
+impl PrutoipaBuildError {
+    /// Completes the location of the error with the parts of `location` it is missing, so
+    /// errors raised deep in the descriptors get the file and package they belong to.
+    pub fn at(mut self, location: &ErrorLocation) -> Self {
+        if let Some(error_location) = self.get_location_mut() {
+            if error_location.file.is_empty() {
+                error_location.file = location.file.clone();
+            }
+            if error_location.package.is_empty() {
+                error_location.package = location.package.clone();
+            }
+            if error_location.path.is_empty() {
+                error_location.path = location.path.clone();
+            }
+            if error_location.position.is_none() {
+                error_location.position = location.position;
+            }
+        }
+
+        self
+    }
+
+    //
+    pub fn get_location(&self) -> Option<&ErrorLocation> {
+        match self {
+            Self::MissingName { location, .. }
+            | Self::UnsupportedSyntax { location, .. }
+            | Self::DuplicateDescriptor { location, .. }
+            | Self::UnknownType { location, .. }
+            | Self::UnexpectedType { location, .. }
+            | Self::UnsupportedType { location, .. }
+            | Self::InvalidField { location, .. }
+            | Self::InvalidDefaultValue { location, .. }
+            | Self::InvalidHttpRule { location, .. } => Some(location.as_ref()),
+            Self::StrictWarning(warning) => Some(&warning.location),
+            _ => None,
+        }
+    }
+
+    fn get_location_mut(&mut self) -> Option<&mut ErrorLocation> {
+        match self {
+            Self::MissingName { location, .. }
+            | Self::UnsupportedSyntax { location, .. }
+            | Self::DuplicateDescriptor { location, .. }
+            | Self::UnknownType { location, .. }
+            | Self::UnexpectedType { location, .. }
+            | Self::UnsupportedType { location, .. }
+            | Self::InvalidField { location, .. }
+            | Self::InvalidDefaultValue { location, .. }
+            | Self::InvalidHttpRule { location, .. } => Some(location.as_mut()),
+            Self::StrictWarning(warning) => Some(&mut warning.location),
+            _ => None,
+        }
+    }
+}
+
+impl ErrorLocation {
+    pub fn new(path: String, position: Option<(i32, i32)>) -> Self {
+        Self {
+            path,
+            position,
+            ..Default::default()
+        }
+    }
+}
+
+impl Display for ErrorLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let file = match self.position {
+            Some((line, column)) if !self.file.is_empty() => {
+                format!("{}:{line}:{column}", self.file)
+            }
+            _ => self.file.clone(),
+        };
+
+        match (self.path.is_empty(), file.is_empty()) {
+            (true, true) => write!(f, "descriptor set"),
+            (true, false) => write!(f, "'{file}'"),
+            (false, true) => write!(f, "'{}'", self.path),
+            (false, false) => write!(f, "'{}' ({file})", self.path),
+        }
+    }
 }
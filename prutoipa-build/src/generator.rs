@@ -1,7 +1,131 @@
+pub mod components;
 pub mod enumeration;
 pub mod message;
+pub mod openapi;
+pub mod paths;
+pub mod references;
+pub mod schema;
+pub mod well_known;
 
-use std::fmt::{Display, Formatter};
+use heck::{ToLowerCamelCase, ToSnakeCase};
+use std::{
+    collections::BTreeMap,
+    fmt::{Debug, Display, Formatter},
+    io::Write,
+    sync::Arc,
+};
+
+use crate::{
+    error::PrutoipaBuildError,
+    generator::message::OneOfStyle,
+    ident::{to_rust_path, to_schema_name},
+    package::PackageFile,
+};
+
+/// How 64-bit integers are represented in the generated schemas.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JsonMapping {
+    /// Decimal strings, as proto3 JSON serializes `int64`, `uint64` and their variants.
+    #[default]
+    Canonical,
+    /// Either decimal strings or numbers, as proto3 JSON parsers accept both.
+    Lenient,
+    /// Numbers, as serializers ignoring the proto3 JSON mapping write them.
+    Numeric,
+}
+
+/// How enums are represented in the generated schemas.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EnumRepresentation {
+    /// Value names, as proto3 JSON serializes enums.
+    #[default]
+    String,
+    /// Value numbers, as `prost` stores enums.
+    Integer,
+    /// Either value names or numbers, as proto3 JSON parsers accept both.
+    Either,
+}
+
+/// Which properties of the generated schemas are required or nullable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PresencePolicy {
+    /// Proto3 scalars and proto2 `required` fields are required, as `prost` always sets them.
+    #[default]
+    Strict,
+    /// Only proto2 `required` fields are required, as proto3 JSON omits zero values, and fields
+    /// declared `optional` are nullable.
+    WireAccurate,
+}
+
+/// Presence of a single property, overriding the `PresencePolicy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyPresence {
+    Required,
+    Optional,
+    Nullable,
+}
+
+/// How property names are derived from field names.
+#[derive(Clone, Default)]
+pub enum PropertyNaming {
+    /// Field names as written at the proto files.
+    ProtoName,
+    /// `json_name` of the fields, as proto3 JSON serializes them.
+    #[default]
+    JsonName,
+    /// `snake_case` field names, as `prost` names struct fields.
+    SnakeCase,
+    /// `camelCase` field names.
+    CamelCase,
+    /// Names returned by a callback receiving the field name as written at the proto files.
+    Custom(Arc<dyn Fn(&str) -> String + Send + Sync>),
+}
+
+impl PropertyNaming {
+    pub fn get_property_name(&self, name: &str, json_name: &str) -> String {
+        match self {
+            Self::ProtoName => name.to_string(),
+            Self::JsonName => json_name.to_string(),
+            Self::SnakeCase => name.to_snake_case(),
+            Self::CamelCase => name.to_lower_camel_case(),
+            Self::Custom(callback) => callback(name),
+        }
+    }
+}
+
+impl Debug for PropertyNaming {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ProtoName => write!(f, "ProtoName"),
+            Self::JsonName => write!(f, "JsonName"),
+            Self::SnakeCase => write!(f, "SnakeCase"),
+            Self::CamelCase => write!(f, "CamelCase"),
+            Self::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+/// Info of the generated OpenAPI documents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenApiInfo {
+    pub title: String,
+    pub version: String,
+}
+
+/// Settings of the `Builder` the generators depend on.
+#[derive(Debug, Clone, Default)]
+pub struct GeneratorOptions {
+    pub generate_enum_values: bool,
+    pub enum_representation: EnumRepresentation,
+    pub oneof_style: OneOfStyle,
+    pub json_mapping: JsonMapping,
+    pub markdown_comments: bool,
+    pub property_naming: PropertyNaming,
+    pub implicit_defaults: bool,
+    pub presence_policy: PresencePolicy,
+    pub presence_overrides: BTreeMap<String, PropertyPresence>,
+    pub openapi_info: Option<OpenApiInfo>,
+}
 
 #[derive(Debug, Clone, Copy)]
 struct Indent(usize);
@@ -14,3 +138,69 @@ impl Display for Indent {
         Ok(())
     }
 }
+
+/// Writes the head of a `ToSchema` implementation, up to its schema name.
+fn write_head<W: Write>(
+    writer: &mut W,
+    package_name: String,
+    name: String,
+) -> Result<(), PrutoipaBuildError> {
+    let i_00 = Indent(0);
+    let i_04 = Indent(1);
+    let i_08 = Indent(2);
+    let i_12 = Indent(3);
+
+    let rust_path = to_rust_path(&name);
+    let schema_name = to_schema_name(&package_name, &name);
+
+    let lines_to_write = vec![
+        format!("{i_00}impl<'__s> utoipa::ToSchema<'__s> for {rust_path} {{"),
+        format!("{i_04}fn schema() -> (&'__s str, utoipa::openapi::RefOr<utoipa::openapi::schema::Schema>) {{"),
+        format!("{i_08}("),
+        format!("{i_12}\"{schema_name}\","),
+    ];
+
+    write_lines(writer, lines_to_write)
+}
+
+/// Writes the tail of a `ToSchema` implementation, after its schema.
+fn write_tail<W: Write>(writer: &mut W) -> Result<(), PrutoipaBuildError> {
+    let i_00 = Indent(0);
+    let i_04 = Indent(1);
+    let i_08 = Indent(2);
+    let i_16 = Indent(4);
+
+    let lines_to_write = vec![
+        format!("{i_16}.into()"),
+        format!("{i_08})"),
+        format!("{i_04}}}"),
+        format!("{i_00}}}"),
+    ];
+
+    write_lines(writer, lines_to_write)
+}
+
+fn write_lines<W: Write>(writer: &mut W, lines: Vec<String>) -> Result<(), PrutoipaBuildError> {
+    lines
+        .into_iter()
+        .map(|line| writeln!(writer, "{line}"))
+        .collect::<Result<Vec<()>, std::io::Error>>()?;
+
+    Ok(())
+}
+
+pub fn generate_header<W: Write>(
+    writer: &mut W,
+    files: Vec<PackageFile>,
+) -> Result<(), PrutoipaBuildError> {
+    let file_names = files
+        .into_iter()
+        .map(|file| file.get_name())
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    writeln!(writer, "// This file is @generated by prutoipa-build.")?;
+    writeln!(writer, "// Source files: {file_names}")?;
+
+    Ok(())
+}
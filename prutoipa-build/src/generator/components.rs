@@ -0,0 +1,60 @@
+use std::{collections::BTreeMap, io::Write};
+
+use crate::{
+    descriptor::Descriptor,
+    error::PrutoipaBuildError,
+    generator::{message::OneOfStyle, write_lines, GeneratorOptions, Indent},
+    ident::to_rust_path,
+};
+
+/// Returns the paths of the descriptors of a package that implement `ToSchema`.
+pub fn get_component_paths(
+    descriptors: &BTreeMap<String, Descriptor>,
+    options: &GeneratorOptions,
+) -> Vec<String> {
+    descriptors
+        .iter()
+        .flat_map(|(descriptor_name, descriptor)| {
+            let mut paths = vec![descriptor_name.clone()];
+            if let (Descriptor::Message(message), OneOfStyle::Nested) =
+                (descriptor, options.oneof_style)
+            {
+                paths.extend(
+                    message
+                        .get_oneofs()
+                        .into_iter()
+                        .map(|oneof| format!("{descriptor_name}.{}", oneof.get_name())),
+                );
+            }
+
+            paths
+        })
+        .collect()
+}
+
+/// Writes the `components` function, returning the schemas of all the descriptors of the
+/// package so they can be registered at once.
+pub fn generate_components<W: Write>(
+    writer: &mut W,
+    package_name: String,
+    component_paths: Vec<String>,
+) -> Result<(), PrutoipaBuildError> {
+    let i_00 = Indent(0);
+    let i_04 = Indent(1);
+    let i_08 = Indent(2);
+
+    let mut lines_to_write = vec![
+        format!("{i_00}/// Returns the schemas of all the messages and enums of the `{package_name}` package."),
+        format!("{i_00}pub fn components() -> utoipa::openapi::Components {{"),
+        format!("{i_04}utoipa::openapi::ComponentsBuilder::new()"),
+    ];
+    lines_to_write.extend(
+        component_paths
+            .iter()
+            .map(|path| format!("{i_08}.schema_from::<{}>()", to_rust_path(path))),
+    );
+    lines_to_write.push(format!("{i_08}.build()"));
+    lines_to_write.push(format!("{i_00}}}"));
+
+    write_lines(writer, lines_to_write)
+}
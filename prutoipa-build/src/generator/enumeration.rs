@@ -3,7 +3,11 @@ use std::io::Write;
 use crate::{
     descriptor::enum_descriptor::{EnumDescriptor, EnumValue},
     error::PrutoipaBuildError,
-    generator::Indent,
+    generator::{
+        schema::get_schema_component, write_head, write_lines, write_tail, EnumRepresentation,
+        GeneratorOptions,
+    },
+    schema::{KnownFormat, ObjectSchema, Schema, SchemaType},
 };
 
 pub fn generate_enum<W: Write>(
@@ -11,99 +15,98 @@ pub fn generate_enum<W: Write>(
     package_name: String,
     name: String,
     enum_descriptor: EnumDescriptor,
-    generate_enum_values: bool,
+    options: &GeneratorOptions,
 ) -> Result<(), PrutoipaBuildError> {
-    write_head(writer, package_name, name)?;
-    let res = write_values(writer, enum_descriptor.get_values(), generate_enum_values);
+    let schema = get_enum_schema(enum_descriptor, options);
+
+    write_head(writer, package_name.clone(), name)?;
+    let res = write_lines(writer, get_schema_component(3, &package_name, &schema));
     write_tail(writer)?;
 
     res
 }
 
-fn write_head<W: Write>(
-    writer: &mut W,
-    package_name: String,
-    name: String,
-) -> Result<(), PrutoipaBuildError> {
-    let i_00 = Indent(0);
-    let i_04 = Indent(1);
-    let i_08 = Indent(2);
-    let i_12 = Indent(3);
-    let i_16 = Indent(4);
-    let i_20 = Indent(5);
-
-    let lines_to_write = vec![
-        format!("{i_00}impl<'__s> utoipa::ToSchema<'__s> for {name} {{"),
-        format!("{i_04}fn schema() -> (&'__s str, utoipa::openapi::RefOr<utoipa::openapi::schema::Schema>) {{"),
-        format!("{i_08}("),
-        format!("{i_12}\"{package_name}.{name}\","),
-        format!("{i_12}utoipa::openapi::ObjectBuilder::new()"),
-        format!("{i_16}.schema_type(utoipa::openapi::SchemaType::Integer)"),
-        format!("{i_16}.format(Some(utoipa::openapi::SchemaFormat::KnownFormat("),
-        format!("{i_20}utoipa::openapi::KnownFormat::Int32"),
-        format!("{i_16})))"),
-    ];
-
-    lines_to_write
-        .into_iter()
-        .map(|line| writeln!(writer, "{line}"))
-        .collect::<Result<Vec<()>, std::io::Error>>()?;
-
-    Ok(())
+pub fn get_enum_schema(enum_descriptor: EnumDescriptor, options: &GeneratorOptions) -> Schema {
+    let enum_values = enum_descriptor.get_values();
+
+    let schema = match options.enum_representation {
+        EnumRepresentation::String => Schema::Object(get_enum_names_schema(&enum_values)),
+        EnumRepresentation::Integer => Schema::Object(get_enum_numbers_schema(
+            &enum_values,
+            options.generate_enum_values,
+        )),
+        // Proto3 JSON parsers accept both names and numbers
+        EnumRepresentation::Either => Schema::one_of(vec![
+            Schema::Object(get_enum_names_schema(&enum_values)),
+            Schema::Object(get_enum_numbers_schema(
+                &enum_values,
+                options.generate_enum_values,
+            )),
+        ]),
+    };
+
+    schema.annotate(
+        Some(get_enum_description(&enum_descriptor, options)),
+        enum_descriptor.is_deprecated(),
+    )
 }
 
-fn write_tail<W: Write>(writer: &mut W) -> Result<(), PrutoipaBuildError> {
-    let i_00 = Indent(0);
-    let i_04 = Indent(1);
-    let i_08 = Indent(2);
-    let i_16 = Indent(4);
-
-    let lines_to_write = vec![
-        format!("{i_16}.into()"),
-        format!("{i_08})"),
-        format!("{i_04}}}"),
-        format!("{i_00}}}"),
-    ];
-
-    lines_to_write
+/// Returns the description of an enum followed by its values, as the schemas can not describe
+/// them one by one.
+pub fn get_enum_description(
+    enum_descriptor: &EnumDescriptor,
+    options: &GeneratorOptions,
+) -> String {
+    let mut description = enum_descriptor
+        .get_comments()
+        .get_description(options.markdown_comments)
+        .map(|description| format!("{description}\n\n"))
+        .unwrap_or_default();
+    description.push_str("Values:");
+    enum_descriptor
+        .get_values()
         .into_iter()
-        .map(|line| writeln!(writer, "{line}"))
-        .collect::<Result<Vec<()>, std::io::Error>>()?;
+        .for_each(|enum_value| {
+            description
+                .push_str(format!("\n\n{} = {}", enum_value.number, enum_value.name).as_str());
+
+            // See `Schema::annotate` for deprecated values
+            if enum_value.deprecated {
+                description.push_str(" (deprecated)");
+            }
+
+            if let Some(value_description) = enum_value
+                .comments
+                .get_description(options.markdown_comments)
+            {
+                description.push_str(format!(": {value_description}").as_str());
+            }
+        });
 
-    Ok(())
+    description
 }
 
-fn write_values<W: Write>(
-    writer: &mut W,
-    enum_values: Vec<EnumValue>,
-    generate_enum_values: bool,
-) -> Result<(), PrutoipaBuildError> {
-    let i_16 = Indent(4);
-    let i_20 = Indent(5);
-
-    //
-    let mut description = format!("{i_16}.description(Some(\"Values:");
-    enum_values.clone().into_iter().for_each(|enum_value| {
-        description.push_str(format!("\\n\\n{} = {}", enum_value.number, enum_value.name).as_str());
-    });
-    description.push_str("\"))");
-
-    writeln!(writer, "{description}")?;
-
-    //
-    if generate_enum_values {
-        let mut enum_values_lines = Vec::<String>::new();
-        enum_values_lines.push(format!("{i_16}.enum_values(Some(vec!["));
-        enum_values.into_iter().for_each(|enum_value| {
-            enum_values_lines.push(format!("{i_20}\"{}\",", enum_value.number));
-        });
-        enum_values_lines.push(format!("{i_16}]))"));
-
-        enum_values_lines
-            .into_iter()
-            .map(|line| writeln!(writer, "{line}"))
-            .collect::<Result<Vec<()>, std::io::Error>>()?;
+fn get_enum_names_schema(enum_values: &[EnumValue]) -> ObjectSchema {
+    ObjectSchema {
+        enum_values: Some(
+            enum_values
+                .iter()
+                .map(|enum_value| enum_value.name.clone())
+                .collect(),
+        ),
+        ..ObjectSchema::typed(SchemaType::String)
     }
+}
 
-    Ok(())
+fn get_enum_numbers_schema(enum_values: &[EnumValue], generate_enum_values: bool) -> ObjectSchema {
+    ObjectSchema {
+        format: Some(KnownFormat::Int32),
+        enum_values: generate_enum_values.then(|| {
+            enum_values
+                .iter()
+                .map(|enum_value| enum_value.number.to_string())
+                .collect()
+        }),
+        ..ObjectSchema::typed(SchemaType::Integer)
+    }
 }
@@ -1,231 +1,458 @@
-use std::io::Write;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+};
 
 use crate::{
     descriptor::message_descriptor::{
-        field::{Field, FieldModifier, FieldType, ScalarType},
+        default_value::{to_base64, DefaultValue},
+        field::{Field, FieldModifier, FieldPresence, FieldType, ScalarType},
+        oneof::OneOf,
         MessageDescriptor,
     },
-    error::PrutoipaBuildError,
-    generator::Indent,
+    error::{ErrorLocation, PrutoipaBuildError},
+    generator::{
+        references::get_recursive_schemas, schema::get_schema_component,
+        well_known::get_well_known_schema, write_head, write_lines, write_tail, EnumRepresentation,
+        GeneratorOptions, JsonMapping, PresencePolicy, PropertyPresence,
+    },
+    ident::{to_json_name, to_schema_name},
+    package::Package,
+    schema::{AdditionalProperties, Literal, ObjectSchema, Schema, SchemaType},
 };
 
+/// How one-of groups are represented in the generated schemas.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OneOfStyle {
+    /// Members are flattened into the message, as `pbjson` serializes them, and documented as a
+    /// `oneOf` of single property objects.
+    #[default]
+    Flattened,
+    /// Each group is a property referencing the schema of the enum `prost` generates for it at
+    /// `message::OneofName`.
+    Nested,
+}
+
 pub fn generate_message<W: Write>(
     writer: &mut W,
     package_name: String,
     name: String,
     message: MessageDescriptor,
+    packages: &BTreeMap<String, Package>,
+    options: &GeneratorOptions,
 ) -> Result<(), PrutoipaBuildError> {
-    write_head(writer, package_name.clone(), name)?;
-
-    let res = message
-        .get_fields()
-        .into_iter()
-        .map(|field| write_field(writer, package_name.clone(), field))
-        .collect::<Result<(), PrutoipaBuildError>>();
-
+    write_head(writer, package_name.clone(), name.clone())?;
+    let res = get_message_schema(&package_name, &name, &message, packages, options)
+        .and_then(|schema| write_lines(writer, get_schema_component(3, &package_name, &schema)));
     write_tail(writer)?;
-
-    res.map(|_| ())
-}
-
-fn write_head<W: Write>(
-    writer: &mut W,
-    package_name: String,
-    name: String,
-) -> Result<(), PrutoipaBuildError> {
-    let i_00 = Indent(0);
-    let i_04 = Indent(1);
-    let i_08 = Indent(2);
-    let i_12 = Indent(3);
-
-    let lines_to_write = vec![
-        format!("{i_00}impl<'__s> utoipa::ToSchema<'__s> for {name} {{"),
-        format!("{i_04}fn schema() -> (&'__s str, utoipa::openapi::RefOr<utoipa::openapi::schema::Schema>) {{"),
-        format!("{i_08}("),
-        format!("{i_12}\"{package_name}.{name}\","),
-        format!("{i_12}utoipa::openapi::ObjectBuilder::new()"),
-    ];
-
-    lines_to_write
-        .into_iter()
-        .map(|line| writeln!(writer, "{line}"))
-        .collect::<Result<Vec<()>, std::io::Error>>()?;
+    res?;
+
+    if options.oneof_style == OneOfStyle::Nested {
+        message
+            .get_oneofs()
+            .into_iter()
+            .map(|oneof| {
+                let oneof_path = format!("{name}.{}", oneof.get_name());
+
+                write_head(writer, package_name.clone(), oneof_path.clone())?;
+                let res =
+                    get_nested_oneof_schema(&package_name, &oneof_path, oneof, packages, options)
+                        .and_then(|schema| {
+                            write_lines(writer, get_schema_component(3, &package_name, &schema))
+                        });
+                write_tail(writer)?;
+
+                res
+            })
+            .collect::<Result<Vec<()>, PrutoipaBuildError>>()?;
+    }
 
     Ok(())
 }
 
-fn write_tail<W: Write>(writer: &mut W) -> Result<(), PrutoipaBuildError> {
-    let i_00 = Indent(0);
-    let i_04 = Indent(1);
-    let i_08 = Indent(2);
-    let i_16 = Indent(4);
+/// Returns the schema of a message, as its `ToSchema` implementation returns it.
+pub fn get_message_schema(
+    package_name: &str,
+    name: &str,
+    message: &MessageDescriptor,
+    packages: &BTreeMap<String, Package>,
+    options: &GeneratorOptions,
+) -> Result<Schema, PrutoipaBuildError> {
+    let recursive_schemas = get_recursive_schemas(
+        packages,
+        options,
+        &(package_name.to_string(), name.to_string()),
+    );
+    let schema = match options.oneof_style {
+        OneOfStyle::Flattened if !message.get_oneofs().is_empty() => {
+            get_flattened_message_schema(package_name, message, options, &recursive_schemas)?
+        }
+        _ => Schema::Object(get_object_schema(
+            package_name,
+            name,
+            message,
+            options,
+            &recursive_schemas,
+        )?),
+    };
+    let description = message
+        .get_comments()
+        .get_description(options.markdown_comments);
+
+    Ok(schema.annotate(description, message.is_deprecated()))
+}
 
-    let lines_to_write = vec![
-        format!("{i_16}.into()"),
-        format!("{i_08})"),
-        format!("{i_04}}}"),
-        format!("{i_00}}}"),
-    ];
+/// Returns the schema of a one-of group at `Message.oneof_name`, in `OneOfStyle::Nested`.
+pub fn get_nested_oneof_schema(
+    package_name: &str,
+    oneof_path: &str,
+    oneof: OneOf,
+    packages: &BTreeMap<String, Package>,
+    options: &GeneratorOptions,
+) -> Result<Schema, PrutoipaBuildError> {
+    let recursive_schemas = get_recursive_schemas(
+        packages,
+        options,
+        &(package_name.to_string(), oneof_path.to_string()),
+    );
+
+    get_oneof_schema(package_name, oneof, options, &recursive_schemas)
+}
 
-    lines_to_write
+fn get_object_schema(
+    package_name: &str,
+    name: &str,
+    message: &MessageDescriptor,
+    options: &GeneratorOptions,
+    recursive_schemas: &BTreeSet<(String, String)>,
+) -> Result<ObjectSchema, PrutoipaBuildError> {
+    let mut object = message
+        .get_fields()
         .into_iter()
-        .map(|line| writeln!(writer, "{line}"))
-        .collect::<Result<Vec<()>, std::io::Error>>()?;
+        .try_fold(ObjectSchema::default(), |object, field| {
+            add_field_property(package_name, object, field, options, recursive_schemas)
+        })?;
+
+    if options.oneof_style == OneOfStyle::Nested {
+        for oneof in message.get_oneofs() {
+            let oneof_name = oneof.get_name();
+            let property_name = options
+                .property_naming
+                .get_property_name(&oneof_name, &to_json_name(&oneof_name));
+
+            object = object.property(
+                property_name,
+                get_descriptor_reference(
+                    package_name.to_string(),
+                    format!("{name}.{oneof_name}"),
+                    recursive_schemas,
+                ),
+            );
+        }
+    }
 
-    Ok(())
+    Ok(object)
 }
 
-fn write_field<W: Write>(
-    writer: &mut W,
-    package_name: String,
-    field: Field,
-) -> Result<(), PrutoipaBuildError> {
-    match field.get_field_type() {
-        FieldType::Scalar(scalar_type) => write_field_scalar(writer, field, scalar_type),
-        FieldType::Object {
-            package,
-            descriptor,
-        } => write_field_object(writer, field, package_name, package, descriptor),
+fn get_flattened_message_schema(
+    package_name: &str,
+    message: &MessageDescriptor,
+    options: &GeneratorOptions,
+    recursive_schemas: &BTreeSet<(String, String)>,
+) -> Result<Schema, PrutoipaBuildError> {
+    let mut items = vec![Schema::Object(get_object_schema(
+        package_name,
+        "",
+        message,
+        options,
+        recursive_schemas,
+    )?)];
+    for oneof in message.get_oneofs() {
+        items.push(get_oneof_schema(
+            package_name,
+            oneof,
+            options,
+            recursive_schemas,
+        )?);
     }
+
+    Ok(Schema::all_of(items))
 }
 
-fn write_field_scalar<W: Write>(
-    writer: &mut W,
-    field: Field,
-    scalar_type: ScalarType,
-) -> Result<(), PrutoipaBuildError> {
-    let i_16 = Indent(4);
-    let i_20 = Indent(5);
+fn get_oneof_schema(
+    package_name: &str,
+    oneof: OneOf,
+    options: &GeneratorOptions,
+    recursive_schemas: &BTreeSet<(String, String)>,
+) -> Result<Schema, PrutoipaBuildError> {
+    let description = oneof
+        .get_comments()
+        .get_description(options.markdown_comments);
+
+    let items = oneof
+        .get_fields()
+        .into_iter()
+        .map(|field| {
+            let field_name = options
+                .property_naming
+                .get_property_name(&field.get_name(), &field.get_json_name());
+            let object = add_field_property(
+                package_name,
+                ObjectSchema::default(),
+                field,
+                options,
+                recursive_schemas,
+            )?;
+
+            Ok(Schema::Object(object.required(field_name)))
+        })
+        .collect::<Result<Vec<Schema>, PrutoipaBuildError>>()?;
+
+    Ok(Schema::one_of(items).description(description))
+}
 
-    let field_modifier = field.get_field_modifier();
-    let field_name = field.get_name();
+fn add_field_property(
+    package_name: &str,
+    object: ObjectSchema,
+    field: Field,
+    options: &GeneratorOptions,
+    recursive_schemas: &BTreeSet<(String, String)>,
+) -> Result<ObjectSchema, PrutoipaBuildError> {
+    let field_name = options
+        .property_naming
+        .get_property_name(&field.get_name(), &field.get_json_name());
+    let mut description = field
+        .get_comments()
+        .get_description(options.markdown_comments);
+    let default_value = field.get_default_value(options.implicit_defaults);
+    let presence = get_property_presence(&field, options);
+    let annotated = description.is_some()
+        || field.is_deprecated()
+        || default_value.is_some()
+        || presence == PropertyPresence::Nullable;
 
     //
-    let mut property_str = Vec::<String>::new();
-    property_str.push(format!("{i_16}.property("));
-    property_str.push(format!("{i_20}\"{field_name}\","));
-
-    if field_modifier == FieldModifier::Repeated {
-        property_str.push(format!(
-            "{i_20}utoipa::openapi::ArrayBuilder::from(utoipa::openapi::Array::new("
-        ));
-
-        property_str.append(&mut get_field_scalar_component(6, scalar_type));
-
-        property_str.push(format!("{i_20}))"));
+    let mut schema = if field.get_field_modifier() == FieldModifier::Repeated {
+        Schema::array(get_field_schema(
+            package_name,
+            field.get_field_type(),
+            options,
+            recursive_schemas,
+        )?)
     } else {
-        property_str.append(&mut get_field_scalar_component(5, scalar_type));
+        match field.get_field_type() {
+            // References can not be annotated, so they are wrapped
+            field_type @ (FieldType::Object { .. } | FieldType::Enum { .. }) if annotated => {
+                Schema::all_of(vec![get_field_schema(
+                    package_name,
+                    field_type,
+                    options,
+                    recursive_schemas,
+                )?])
+            }
+            FieldType::Map { key, value } => get_field_map_schema(
+                package_name,
+                key,
+                *value,
+                options,
+                recursive_schemas,
+                // Merged with the description of the keys
+                description.take(),
+            )?,
+            field_type => get_field_schema(package_name, field_type, options, recursive_schemas)?,
+        }
+    };
+
+    if presence == PropertyPresence::Nullable {
+        schema = schema.nullable(true);
     }
 
-    property_str.push(format!("{i_16})"));
-
-    for property_str_line in property_str {
-        writeln!(writer, "{property_str_line}")?;
-    }
+    schema = schema
+        .default(
+            default_value.map(|default_value| get_default_value_literal(default_value, options)),
+        )
+        .annotate(description, field.is_deprecated());
 
     //
-    if field_modifier == FieldModifier::Required {
-        let required_str = format!("{i_16}.required(\"{field_name}\")");
-        writeln!(writer, "{required_str}")?;
-    }
+    let object = object.property(field_name.clone(), schema);
 
-    Ok(())
+    match presence {
+        PropertyPresence::Required => Ok(object.required(field_name)),
+        _ => Ok(object),
+    }
 }
 
-fn get_field_scalar_component(base_indent: usize, scalar_type: ScalarType) -> Vec<String> {
-    let mut property_str = Vec::<String>::new();
-
-    let i_00 = Indent(base_indent);
-    let i_04 = Indent(base_indent + 1);
-    let i_08 = Indent(base_indent + 2);
-
-    let schema_type = scalar_type.get_utoipa_type();
-    let schema_format = scalar_type.get_utoipa_format();
-
-    //
-    property_str.push(format!("{i_00}utoipa::openapi::ObjectBuilder::new()"));
-    property_str.push(format!(
-        "{i_04}.schema_type(utoipa::openapi::SchemaType::{schema_type})"
-    ));
-
-    if let Some(known_format) = schema_format {
-        property_str.push(format!(
-            "{i_04}.format(Some(utoipa::openapi::SchemaFormat::KnownFormat("
-        ));
-        property_str.push(format!(
-            "{i_08}utoipa::openapi::KnownFormat::{known_format}"
-        ));
-        property_str.push(format!("{i_04})))"));
+pub fn get_property_presence(field: &Field, options: &GeneratorOptions) -> PropertyPresence {
+    if let Some(presence) = options.presence_overrides.get(&field.get_full_name()) {
+        return *presence;
     }
 
-    property_str
+    match options.presence_policy {
+        PresencePolicy::Strict => match field.get_field_modifier() {
+            FieldModifier::Required => PropertyPresence::Required,
+            _ => PropertyPresence::Optional,
+        },
+        PresencePolicy::WireAccurate => match field.get_field_presence() {
+            FieldPresence::Required => PropertyPresence::Required,
+            FieldPresence::Optional => PropertyPresence::Nullable,
+            FieldPresence::Unspecified => PropertyPresence::Optional,
+        },
+    }
 }
 
-fn write_field_object<W: Write>(
-    writer: &mut W,
-    field: Field,
-    current_package: String,
-    field_package: String,
-    field_descriptor: String,
-) -> Result<(), PrutoipaBuildError> {
-    let i_16 = Indent(4);
-    let i_20 = Indent(5);
-    let i_24 = Indent(6);
-
-    let field_modifier = field.get_field_modifier();
-    let field_name = field.get_name();
-
-    //
-    let mut property_str = Vec::<String>::new();
-    property_str.push(format!("{i_16}.property("));
-    property_str.push(format!("{i_20}\"{field_name}\","));
-
-    if field_modifier == FieldModifier::Repeated {
-        property_str.push(format!(
-            "{i_20}utoipa::openapi::ArrayBuilder::from(utoipa::openapi::Array::new("
-        ));
-
-        property_str.push(get_field_object_component(
-            i_24,
-            current_package,
-            field_package,
-            field_descriptor,
-        ));
+/// Returns a default value as proto3 JSON encodes it.
+fn get_default_value_literal(default_value: DefaultValue, options: &GeneratorOptions) -> Literal {
+    match default_value {
+        DefaultValue::Int32(value) => Literal::Int32(value),
+        DefaultValue::Int64(value) if options.json_mapping == JsonMapping::Numeric => {
+            Literal::Int64(value)
+        }
+        DefaultValue::Int64(value) => Literal::String(value.to_string()),
+        DefaultValue::UInt32(value) => Literal::UInt32(value),
+        DefaultValue::UInt64(value) if options.json_mapping == JsonMapping::Numeric => {
+            Literal::UInt64(value)
+        }
+        DefaultValue::UInt64(value) => Literal::String(value.to_string()),
+        DefaultValue::Float(value) if value.is_nan() => Literal::String("NaN".to_string()),
+        DefaultValue::Float(value) if value == f64::INFINITY => {
+            Literal::String("Infinity".to_string())
+        }
+        DefaultValue::Float(value) if value == f64::NEG_INFINITY => {
+            Literal::String("-Infinity".to_string())
+        }
+        DefaultValue::Float(value) => Literal::Double(value),
+        DefaultValue::Bool(value) => Literal::Bool(value),
+        DefaultValue::String(value) => Literal::String(value),
+        DefaultValue::Bytes(value) => Literal::String(to_base64(&value)),
+        DefaultValue::Enum { number, .. }
+            if options.enum_representation == EnumRepresentation::Integer =>
+        {
+            Literal::Int32(number)
+        }
+        DefaultValue::Enum { name, .. } | DefaultValue::EnumName(name) => Literal::String(name),
+        DefaultValue::EnumNumber(number) => Literal::Int32(number),
+    }
+}
 
-        property_str.push(format!("{i_20}))"));
-    } else {
-        property_str.push(get_field_object_component(
-            i_20,
+pub fn get_field_schema(
+    current_package: &str,
+    field_type: FieldType,
+    options: &GeneratorOptions,
+    recursive_schemas: &BTreeSet<(String, String)>,
+) -> Result<Schema, PrutoipaBuildError> {
+    match field_type {
+        FieldType::Scalar(scalar_type) => {
+            Ok(get_field_scalar_schema(scalar_type, options.json_mapping))
+        }
+        FieldType::Object {
+            package,
+            descriptor,
+        }
+        | FieldType::Enum {
+            package,
+            descriptor,
+        } => Ok(get_descriptor_reference(
+            package,
+            descriptor,
+            recursive_schemas,
+        )),
+        FieldType::Map { key, value } => get_field_map_schema(
             current_package,
-            field_package,
-            field_descriptor,
-        ));
+            key,
+            *value,
+            options,
+            recursive_schemas,
+            None,
+        ),
+        FieldType::WellKnown(well_known_type) => {
+            Ok(get_well_known_schema(well_known_type, options.json_mapping))
+        }
+        FieldType::Unresolved {
+            type_name,
+            scope,
+            kind: _,
+        } => Err(PrutoipaBuildError::UnknownType {
+            location: ErrorLocation::new(scope, None).into(),
+            type_name,
+        }),
     }
+}
 
-    property_str.push(format!("{i_16})"));
+pub fn get_field_scalar_schema(scalar_type: ScalarType, json_mapping: JsonMapping) -> Schema {
+    // Proto3 JSON encodes 64-bit integers as strings, but parsers accept numbers as well
+    match (scalar_type.get_string_pattern(), json_mapping) {
+        (Some(pattern), JsonMapping::Canonical) => {
+            Schema::Object(get_field_string_schema(scalar_type, pattern))
+        }
+        (Some(pattern), JsonMapping::Lenient) => Schema::one_of(vec![
+            Schema::Object(get_field_string_schema(scalar_type, pattern)),
+            Schema::Object(get_field_number_schema(scalar_type)),
+        ]),
+        _ => Schema::Object(get_field_number_schema(scalar_type)),
+    }
+}
 
-    for property_str_line in property_str {
-        writeln!(writer, "{property_str_line}")?;
+fn get_field_number_schema(scalar_type: ScalarType) -> ObjectSchema {
+    ObjectSchema {
+        format: scalar_type.get_utoipa_format(),
+        ..ObjectSchema::typed(scalar_type.get_utoipa_type())
     }
+}
 
-    //
-    if field_modifier == FieldModifier::Required {
-        let required_str = format!("{i_16}.required(\"{field_name}\")");
-        writeln!(writer, "{required_str}")?;
+fn get_field_string_schema(scalar_type: ScalarType, pattern: &'static str) -> ObjectSchema {
+    ObjectSchema {
+        format: scalar_type.get_utoipa_format(),
+        pattern: Some(pattern),
+        ..ObjectSchema::typed(SchemaType::String)
     }
+}
 
-    Ok(())
+fn get_field_map_schema(
+    current_package: &str,
+    key: ScalarType,
+    value: FieldType,
+    options: &GeneratorOptions,
+    recursive_schemas: &BTreeSet<(String, String)>,
+    description: Option<String>,
+) -> Result<Schema, PrutoipaBuildError> {
+    let value_schema = get_field_schema(current_package, value, options, recursive_schemas)?;
+    let schema = Schema::Object(ObjectSchema {
+        additional_properties: Some(AdditionalProperties::Schema(Box::new(value_schema))),
+        ..Default::default()
+    });
+
+    Ok(schema.description(get_map_description(description, key)))
 }
 
-fn get_field_object_component(
-    indent: Indent,
-    current_package: String,
-    field_package: String,
-    field_descriptor: String,
-) -> String {
-    if current_package == field_package {
-        format!("{indent}{field_descriptor}::schema().1")
-    } else {
-        format!("{indent}super::{field_package}::{field_descriptor}::schema().1")
+/// Returns the description of a map, merging the description of the field with the one of its
+/// keys. Proto3 JSON always encodes map keys as strings.
+pub fn get_map_description(description: Option<String>, key: ScalarType) -> Option<String> {
+    let description = [
+        description,
+        key.get_map_key_description().map(str::to_string),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<String>>();
+
+    match description.is_empty() {
+        true => None,
+        false => Some(description.join("\n\n")),
     }
 }
+
+/// Returns the schema of a descriptor, referenced by name if it reaches back the schema being
+/// built.
+fn get_descriptor_reference(
+    package: String,
+    path: String,
+    recursive_schemas: &BTreeSet<(String, String)>,
+) -> Schema {
+    let schema = (package, path);
+    if recursive_schemas.contains(&schema) {
+        return Schema::Ref(to_schema_name(&schema.0, &schema.1));
+    }
+
+    let (package, path) = schema;
+    Schema::Descriptor { package, path }
+}
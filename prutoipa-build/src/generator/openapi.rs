@@ -0,0 +1,123 @@
+use std::{collections::BTreeMap, collections::BTreeSet, io::Write};
+
+use crate::{
+    descriptor::message_descriptor::field::FieldType,
+    error::PrutoipaBuildError,
+    generator::{
+        components::get_component_paths, references::get_reached_schemas, write_lines,
+        GeneratorOptions, Indent, OpenApiInfo,
+    },
+    ident::to_relative_rust_path,
+    package::Package,
+};
+
+/// Writes the `openapi` function, returning the document of the package: the operations of its
+/// services, the schemas of the package and the ones they reach, and a tag per service.
+pub fn generate_openapi<W: Write>(
+    writer: &mut W,
+    package: &Package,
+    packages: &BTreeMap<String, Package>,
+    info: &OpenApiInfo,
+    options: &GeneratorOptions,
+) -> Result<(), PrutoipaBuildError> {
+    let i_00 = Indent(0);
+    let i_04 = Indent(1);
+    let i_08 = Indent(2);
+    let i_12 = Indent(3);
+    let i_16 = Indent(4);
+
+    let package_name = package.get_name();
+    let description = package
+        .get_files()
+        .into_iter()
+        .filter_map(|file| {
+            file.get_comments()
+                .get_description(options.markdown_comments)
+        })
+        .collect::<Vec<String>>();
+
+    let mut lines_to_write = vec![
+        format!("{i_00}/// Returns the OpenAPI document of the `{package_name}` package."),
+        format!("{i_00}pub fn openapi() -> utoipa::openapi::OpenApi {{"),
+        format!("{i_04}utoipa::openapi::OpenApiBuilder::new()"),
+        format!("{i_08}.info("),
+        format!("{i_12}utoipa::openapi::InfoBuilder::new()"),
+        format!("{i_16}.title({:?})", info.title),
+        format!("{i_16}.version({:?})", info.version),
+    ];
+    if !description.is_empty() {
+        lines_to_write.push(format!(
+            "{i_16}.description(Some({:?}))",
+            description.join("\n\n")
+        ));
+    }
+    lines_to_write.push(format!("{i_16}.build(),"));
+    lines_to_write.push(format!("{i_08})"));
+    lines_to_write.push(format!("{i_08}.paths(paths())"));
+
+    lines_to_write.push(format!("{i_08}.components(Some("));
+    lines_to_write.push(format!("{i_12}utoipa::openapi::ComponentsBuilder::new()"));
+    lines_to_write.extend(
+        get_reachable_schemas(package, packages, options)
+            .into_iter()
+            .map(|(schema_package, schema_path)| {
+                format!(
+                    "{i_16}.schema_from::<{}>()",
+                    to_relative_rust_path(&package_name, &schema_package, &schema_path)
+                )
+            }),
+    );
+    lines_to_write.push(format!("{i_16}.build(),"));
+    lines_to_write.push(format!("{i_08}))"));
+
+    let services = package.get_services();
+    if !services.is_empty() {
+        lines_to_write.push(format!("{i_08}.tags(Some(["));
+        for (service_name, service) in services {
+            lines_to_write.push(format!("{i_12}utoipa::openapi::tag::TagBuilder::new()"));
+            lines_to_write.push(format!("{i_16}.name({service_name:?})"));
+            if let Some(description) = service
+                .get_comments()
+                .get_description(options.markdown_comments)
+            {
+                lines_to_write.push(format!("{i_16}.description(Some({description:?}))"));
+            }
+            lines_to_write.push(format!("{i_16}.build(),"));
+        }
+        lines_to_write.push(format!("{i_08}]))"));
+    }
+
+    lines_to_write.push(format!("{i_08}.build()"));
+    lines_to_write.push(format!("{i_00}}}"));
+
+    write_lines(writer, lines_to_write)
+}
+
+/// Returns the package and path of the schemas of a package, the ones its methods use and every
+/// schema they reference, even from other packages.
+pub fn get_reachable_schemas(
+    package: &Package,
+    packages: &BTreeMap<String, Package>,
+    options: &GeneratorOptions,
+) -> BTreeSet<(String, String)> {
+    let mut pending = get_component_paths(&package.get_descriptors(), options)
+        .into_iter()
+        .map(|path| (package.get_name(), path))
+        .collect::<Vec<(String, String)>>();
+    pending.extend(
+        package
+            .get_services()
+            .into_values()
+            .flat_map(|service| service.get_methods())
+            .flat_map(|method| [method.get_input_type(), method.get_output_type()])
+            .filter_map(|field_type| match field_type {
+                FieldType::Object {
+                    package,
+                    descriptor,
+                } => Some((package, descriptor)),
+                _ => None,
+            }),
+    );
+
+    get_reached_schemas(packages, options, pending)
+}
@@ -0,0 +1,580 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+};
+
+use crate::{
+    descriptor::{
+        message_descriptor::field::{Field, FieldModifier, FieldType},
+        service_descriptor::{
+            http_rule::{HttpBinding, HttpBody, HttpMethod},
+            Method,
+        },
+        Descriptor,
+    },
+    error::{ErrorLocation, PrutoipaBuildError},
+    generator::{
+        message::get_field_schema, schema::get_schema_component, write_lines, GeneratorOptions,
+        Indent,
+    },
+    package::Package,
+    schema::Schema,
+};
+
+/// What the operations of a package depend on besides their methods.
+pub struct PathsContext<'a> {
+    pub package_name: String,
+    pub packages: &'a BTreeMap<String, Package>,
+    pub options: &'a GeneratorOptions,
+}
+
+/// Operation of a binding. `generate_paths` writes it as code and the standalone documents build
+/// it at once.
+pub struct Operation {
+    pub method: HttpMethod,
+    pub operation_id: String,
+    pub tag: String,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub deprecated: bool,
+    pub parameters: Vec<Parameter>,
+    pub request_body: Option<Schema>,
+    pub response_body: Option<Schema>,
+}
+
+pub struct Parameter {
+    pub name: String,
+    pub parameter_in: ParameterIn,
+    pub description: Option<String>,
+    pub deprecated: bool,
+    pub schema: Schema,
+}
+
+/// Where a parameter is sent, named as the `utoipa::openapi::path::ParameterIn` variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParameterIn {
+    Path,
+    Query,
+}
+
+/// Writes the `paths` function, returning the operations of the services of the package
+/// transcoded from their `google.api.http` annotations.
+pub fn generate_paths<W: Write>(
+    writer: &mut W,
+    package: &Package,
+    packages: &BTreeMap<String, Package>,
+    options: &GeneratorOptions,
+) -> Result<(), PrutoipaBuildError> {
+    let i_00 = Indent(0);
+    let i_04 = Indent(1);
+    let i_08 = Indent(2);
+    let i_12 = Indent(3);
+    let i_16 = Indent(4);
+
+    let package_name = package.get_name();
+    let operations = get_operations(package, packages, options)?;
+
+    let mut lines_to_write = vec![
+        format!("{i_00}/// Returns the operations of the services of the `{package_name}` package, transcoded from their"),
+        format!("{i_00}/// `google.api.http` annotations."),
+        format!("{i_00}pub fn paths() -> utoipa::openapi::Paths {{"),
+        format!("{i_04}utoipa::openapi::PathsBuilder::new()"),
+    ];
+    for (path, path_operations) in operations {
+        lines_to_write.push(format!("{i_08}.path("));
+        lines_to_write.push(format!("{i_12}{path:?},"));
+        lines_to_write.push(format!(
+            "{i_12}utoipa::openapi::path::PathItemBuilder::new()"
+        ));
+        for operation in path_operations {
+            lines_to_write.push(format!("{i_16}.operation("));
+            lines_to_write.append(&mut get_operation_component(5, &package_name, &operation));
+            lines_to_write.push(format!("{i_16})"));
+        }
+        lines_to_write.push(format!("{i_16}.build(),"));
+        lines_to_write.push(format!("{i_08})"));
+    }
+    lines_to_write.push(format!("{i_08}.build()"));
+    lines_to_write.push(format!("{i_00}}}"));
+
+    write_lines(writer, lines_to_write)
+}
+
+/// Returns the operations of the services of a package by path, transcoded from their
+/// `google.api.http` annotations.
+pub fn get_operations(
+    package: &Package,
+    packages: &BTreeMap<String, Package>,
+    options: &GeneratorOptions,
+) -> Result<BTreeMap<String, Vec<Operation>>, PrutoipaBuildError> {
+    let context = PathsContext {
+        package_name: package.get_name(),
+        packages,
+        options,
+    };
+
+    // Operations sharing a path must be added to the same path item
+    let mut operations = BTreeMap::<String, Vec<Operation>>::new();
+    for (service_name, service) in package.get_services() {
+        for method in service.get_methods() {
+            let error_location = ErrorLocation {
+                file: package
+                    .get_descriptor_file(&service_name)
+                    .unwrap_or_default(),
+                package: package.get_name(),
+                ..method.get_error_location()
+            };
+
+            for (idx, binding) in method.get_http_bindings().into_iter().enumerate() {
+                let (path, operation) = get_operation(
+                    &context,
+                    &service_name,
+                    service.is_deprecated(),
+                    &method,
+                    idx,
+                    binding,
+                )
+                .map_err(|err| err.at(&error_location))?;
+
+                operations.entry(path).or_default().push(operation);
+            }
+        }
+    }
+
+    Ok(operations)
+}
+
+/// Returns the path of a binding and its operation.
+fn get_operation(
+    context: &PathsContext,
+    service_name: &str,
+    service_deprecated: bool,
+    method: &Method,
+    idx: usize,
+    binding: HttpBinding,
+) -> Result<(String, Operation), PrutoipaBuildError> {
+    let options = context.options;
+    let input_type = method.get_input_type();
+    let output_type = method.get_output_type();
+
+    let (path, path_parameters) = get_path_parameters(context, &input_type, &binding)?;
+    let parameters = path_parameters
+        .into_iter()
+        .map(|(parameter_name, field)| (parameter_name, ParameterIn::Path, field))
+        .chain(
+            get_query_parameters(context, &input_type, &binding)
+                .into_iter()
+                .map(|(parameter_name, field)| (parameter_name, ParameterIn::Query, field)),
+        )
+        .map(|(parameter_name, parameter_in, field)| {
+            get_parameter(context, parameter_name, parameter_in, &field)
+        })
+        .collect::<Result<Vec<Parameter>, PrutoipaBuildError>>()?;
+
+    let request_body = match &binding.body {
+        HttpBody::None => None,
+        HttpBody::Message => Some(get_field_schema(
+            &context.package_name,
+            input_type.clone(),
+            options,
+            &BTreeSet::new(),
+        )?),
+        HttpBody::Field(field_path) => get_field_path(context, &input_type, field_path)?
+            .last()
+            .map(|field| get_field_value_schema(context, field))
+            .transpose()?,
+    };
+    let response_body = match &binding.response_body {
+        HttpBody::Field(field_path) => get_field_path(context, &output_type, field_path)?
+            .last()
+            .map(|field| get_field_value_schema(context, field))
+            .transpose()?,
+        _ => Some(get_field_schema(
+            &context.package_name,
+            output_type,
+            options,
+            &BTreeSet::new(),
+        )?),
+    };
+
+    let (summary, description) = get_summary_and_description(method, options);
+    let operation = Operation {
+        method: binding.method,
+        operation_id: get_operation_id(service_name, method, idx),
+        tag: service_name.to_string(),
+        summary,
+        description,
+        deprecated: service_deprecated || method.is_deprecated(),
+        parameters,
+        request_body,
+        response_body,
+    };
+
+    Ok((path, operation))
+}
+
+fn get_parameter(
+    context: &PathsContext,
+    name: String,
+    parameter_in: ParameterIn,
+    field: &Field,
+) -> Result<Parameter, PrutoipaBuildError> {
+    Ok(Parameter {
+        name,
+        parameter_in,
+        description: field
+            .get_comments()
+            .get_description(context.options.markdown_comments),
+        deprecated: field.is_deprecated(),
+        schema: get_field_value_schema(context, field)?,
+    })
+}
+
+/// Returns the schema of the values of a field, repeated fields are arrays of them.
+fn get_field_value_schema(
+    context: &PathsContext,
+    field: &Field,
+) -> Result<Schema, PrutoipaBuildError> {
+    let schema = get_field_schema(
+        &context.package_name,
+        field.get_field_type(),
+        context.options,
+        &BTreeSet::new(),
+    )?;
+
+    match field.get_field_modifier() {
+        FieldModifier::Repeated => Ok(Schema::array(schema)),
+        _ => Ok(schema),
+    }
+}
+
+/// Returns the lines of an operation, as the arguments of `PathItemBuilder::operation`.
+fn get_operation_component(
+    base_indent: usize,
+    package_name: &str,
+    operation: &Operation,
+) -> Vec<String> {
+    let i_00 = Indent(base_indent);
+    let i_04 = Indent(base_indent + 1);
+    let i_08 = Indent(base_indent + 2);
+    let i_12 = Indent(base_indent + 3);
+
+    let mut component = vec![
+        format!(
+            "{i_00}utoipa::openapi::PathItemType::{:?},",
+            operation.method
+        ),
+        format!("{i_00}utoipa::openapi::path::OperationBuilder::new()"),
+        format!("{i_04}.operation_id(Some({:?}))", operation.operation_id),
+        format!("{i_04}.tag({:?})", operation.tag),
+    ];
+
+    if let Some(summary) = &operation.summary {
+        component.push(format!("{i_04}.summary(Some({summary:?}))"));
+    }
+    if let Some(description) = &operation.description {
+        component.push(format!("{i_04}.description(Some({description:?}))"));
+    }
+    if operation.deprecated {
+        component.push(format!(
+            "{i_04}.deprecated(Some(utoipa::openapi::Deprecated::True))"
+        ));
+    }
+
+    for parameter in &operation.parameters {
+        component.append(&mut get_parameter_component(
+            base_indent + 1,
+            package_name,
+            parameter,
+        ));
+    }
+
+    if let Some(request_body) = &operation.request_body {
+        component.push(format!("{i_04}.request_body(Some("));
+        component.push(format!(
+            "{i_08}utoipa::openapi::request_body::RequestBodyBuilder::new()"
+        ));
+        component.append(&mut get_content_component(
+            base_indent + 3,
+            package_name,
+            request_body,
+        ));
+        component.push(format!(
+            "{i_12}.required(Some(utoipa::openapi::Required::True))"
+        ));
+        component.push(format!("{i_12}.build(),"));
+        component.push(format!("{i_04}))"));
+    }
+
+    component.push(format!("{i_04}.response("));
+    component.push(format!("{i_08}\"200\","));
+    component.push(format!("{i_08}utoipa::openapi::ResponseBuilder::new()"));
+    component.push(format!("{i_12}.description(\"A successful response.\")"));
+    if let Some(response_body) = &operation.response_body {
+        component.append(&mut get_content_component(
+            base_indent + 3,
+            package_name,
+            response_body,
+        ));
+    }
+    component.push(format!("{i_04})"));
+
+    component
+}
+
+fn get_parameter_component(
+    base_indent: usize,
+    package_name: &str,
+    parameter: &Parameter,
+) -> Vec<String> {
+    let i_00 = Indent(base_indent);
+    let i_04 = Indent(base_indent + 1);
+    let i_08 = Indent(base_indent + 2);
+
+    let mut component = vec![
+        format!("{i_00}.parameter("),
+        format!("{i_04}utoipa::openapi::path::ParameterBuilder::new()"),
+        format!("{i_08}.name({:?})", parameter.name),
+        format!(
+            "{i_08}.parameter_in(utoipa::openapi::path::ParameterIn::{:?})",
+            parameter.parameter_in
+        ),
+    ];
+    if parameter.parameter_in == ParameterIn::Path {
+        component.push(format!("{i_08}.required(utoipa::openapi::Required::True)"));
+    }
+    if let Some(description) = &parameter.description {
+        component.push(format!("{i_08}.description(Some({description:?}))"));
+    }
+    if parameter.deprecated {
+        component.push(format!(
+            "{i_08}.deprecated(Some(utoipa::openapi::Deprecated::True))"
+        ));
+    }
+    component.push(format!("{i_08}.schema(Some("));
+    component.append(&mut get_schema_component(
+        base_indent + 3,
+        package_name,
+        &parameter.schema,
+    ));
+    component.push(format!("{i_08})),"));
+    component.push(format!("{i_00})"));
+
+    component
+}
+
+fn get_content_component(base_indent: usize, package_name: &str, schema: &Schema) -> Vec<String> {
+    let i_00 = Indent(base_indent);
+    let i_04 = Indent(base_indent + 1);
+    let i_08 = Indent(base_indent + 2);
+
+    let mut component = vec![
+        format!("{i_00}.content("),
+        format!("{i_04}\"application/json\","),
+        format!("{i_04}utoipa::openapi::ContentBuilder::new()"),
+        format!("{i_08}.schema("),
+    ];
+    component.append(&mut get_schema_component(
+        base_indent + 3,
+        package_name,
+        schema,
+    ));
+    component.push(format!("{i_08})"));
+    component.push(format!("{i_08}.build(),"));
+    component.push(format!("{i_00})"));
+
+    component
+}
+
+/// Returns the id of the operation of a binding. Additional bindings are numbered from 2, as
+/// `grpc-gateway` does.
+pub fn get_operation_id(service_name: &str, method: &Method, idx: usize) -> String {
+    match idx {
+        0 => format!("{service_name}_{}", method.get_name()),
+        _ => format!("{service_name}_{}{}", method.get_name(), idx + 1),
+    }
+}
+
+/// Returns the summary and description of the operations of a method, the first paragraph of
+/// its comments is the summary.
+pub fn get_summary_and_description(
+    method: &Method,
+    options: &GeneratorOptions,
+) -> (Option<String>, Option<String>) {
+    let description = method
+        .get_comments()
+        .get_description(options.markdown_comments);
+
+    match description.as_deref().map(|d| d.split_once("\n\n")) {
+        Some(Some((summary, description))) => {
+            (Some(summary.to_string()), Some(description.to_string()))
+        }
+        Some(None) => (description, None),
+        None => (None, None),
+    }
+}
+
+/// Returns the path of a binding with its parameters, which are named after the properties they
+/// are bound to.
+pub fn get_path_parameters(
+    context: &PathsContext,
+    input_type: &FieldType,
+    binding: &HttpBinding,
+) -> Result<(String, Vec<(String, Field)>), PrutoipaBuildError> {
+    let mut path = binding.path.clone();
+    let mut parameters = Vec::<(String, Field)>::new();
+
+    for field_path in &binding.path_fields {
+        let fields = get_field_path(context, input_type, field_path)?;
+        let parameter_name = fields
+            .iter()
+            .map(|field| get_property_name(field, context.options))
+            .collect::<Vec<String>>()
+            .join(".");
+        path = path.replace(
+            &format!("{{{field_path}}}"),
+            &format!("{{{parameter_name}}}"),
+        );
+
+        if let Some(field) = fields.last() {
+            parameters.push((parameter_name, field.clone()));
+        }
+    }
+
+    Ok((path, parameters))
+}
+
+/// Returns the query parameters of a binding: fields not bound to the path nor sent at the body.
+/// Fields of singular messages are flattened into parameters like `parent.child`, as
+/// `grpc-gateway` does, while repeated messages, maps and messages containing themselves cannot
+/// be sent at the query.
+pub fn get_query_parameters(
+    context: &PathsContext,
+    input_type: &FieldType,
+    binding: &HttpBinding,
+) -> Vec<(String, Field)> {
+    if binding.body == HttpBody::Message {
+        return Vec::new();
+    }
+
+    get_message_fields(context, input_type)
+        .into_iter()
+        .filter(|field| binding.body != HttpBody::Field(field.get_name()))
+        .flat_map(|field| {
+            get_field_query_parameters(
+                context,
+                binding,
+                field.get_name(),
+                get_property_name(&field, context.options),
+                field,
+                vec![input_type.clone()],
+            )
+        })
+        .collect()
+}
+
+/// Returns the query parameters of a field at `field_path`, named `parameter_name`. Messages
+/// already flattened on the way to the field are in `visited_types`.
+fn get_field_query_parameters(
+    context: &PathsContext,
+    binding: &HttpBinding,
+    field_path: String,
+    parameter_name: String,
+    field: Field,
+    mut visited_types: Vec<FieldType>,
+) -> Vec<(String, Field)> {
+    if binding.path_fields.contains(&field_path) {
+        return Vec::new();
+    }
+
+    match field.get_field_type() {
+        FieldType::Object { .. } if field.get_field_modifier() == FieldModifier::Repeated => {
+            Vec::new()
+        }
+        FieldType::Object { .. } if visited_types.contains(&field.get_field_type()) => Vec::new(),
+        FieldType::Object { .. } => {
+            let field_type = field.get_field_type();
+            visited_types.push(field_type.clone());
+
+            get_message_fields(context, &field_type)
+                .into_iter()
+                .flat_map(|child| {
+                    get_field_query_parameters(
+                        context,
+                        binding,
+                        format!("{field_path}.{}", child.get_name()),
+                        format!(
+                            "{parameter_name}.{}",
+                            get_property_name(&child, context.options)
+                        ),
+                        child,
+                        visited_types.clone(),
+                    )
+                })
+                .collect()
+        }
+        FieldType::Map { .. } => Vec::new(),
+        _ => vec![(parameter_name, field)],
+    }
+}
+
+fn get_property_name(field: &Field, options: &GeneratorOptions) -> String {
+    options
+        .property_naming
+        .get_property_name(&field.get_name(), &field.get_json_name())
+}
+
+/// Returns the fields of a message, including the members of its one-of groups. Other types,
+/// like well-known ones, have no fields.
+fn get_message_fields(context: &PathsContext, message_type: &FieldType) -> Vec<Field> {
+    let message = match message_type {
+        FieldType::Object {
+            package,
+            descriptor,
+        } => context
+            .packages
+            .get(package)
+            .and_then(|package| package.get_descriptor(descriptor)),
+        _ => None,
+    };
+
+    match message {
+        Some(Descriptor::Message(message)) => {
+            let mut fields = message.get_fields();
+            fields.extend(
+                message
+                    .get_oneofs()
+                    .into_iter()
+                    .flat_map(|oneof| oneof.get_fields()),
+            );
+
+            fields
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the fields a field path like `book.id` goes through, starting at `message_type`.
+pub fn get_field_path(
+    context: &PathsContext,
+    message_type: &FieldType,
+    field_path: &str,
+) -> Result<Vec<Field>, PrutoipaBuildError> {
+    let mut fields = Vec::<Field>::new();
+    let mut current_type = message_type.clone();
+
+    for segment in field_path.split('.') {
+        let field = get_message_fields(context, &current_type)
+            .into_iter()
+            .find(|field| field.get_name() == segment)
+            .ok_or_else(|| PrutoipaBuildError::InvalidHttpRule {
+                location: ErrorLocation::default().into(),
+                reason: format!("field '{field_path}' is not defined"),
+            })?;
+
+        current_type = field.get_field_type();
+        fields.push(field);
+    }
+
+    Ok(fields)
+}
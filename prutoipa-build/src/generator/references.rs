@@ -0,0 +1,118 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    descriptor::{message_descriptor::field::FieldType, Descriptor},
+    generator::{message::OneOfStyle, GeneratorOptions},
+    package::Package,
+};
+
+/// Returns the package and path of the schemas a schema references. In `OneOfStyle::Nested`,
+/// one-of groups have schemas of their own at `Message.oneof_name`.
+pub fn get_referenced_schemas(
+    packages: &BTreeMap<String, Package>,
+    options: &GeneratorOptions,
+    schema: &(String, String),
+) -> Vec<(String, String)> {
+    let (package_name, path) = schema;
+    let package = packages.get(package_name);
+
+    match package.and_then(|package| package.get_descriptor(path)) {
+        Some(Descriptor::Message(message)) => {
+            let oneofs = message.get_oneofs();
+            let mut referenced = message
+                .get_fields()
+                .into_iter()
+                .filter_map(|field| get_referenced_schema(field.get_field_type()))
+                .collect::<Vec<(String, String)>>();
+
+            match options.oneof_style {
+                OneOfStyle::Flattened => referenced.extend(
+                    oneofs
+                        .into_iter()
+                        .flat_map(|oneof| oneof.get_fields())
+                        .filter_map(|field| get_referenced_schema(field.get_field_type())),
+                ),
+                OneOfStyle::Nested => {
+                    referenced.extend(oneofs.into_iter().map(|oneof| {
+                        (package_name.clone(), format!("{path}.{}", oneof.get_name()))
+                    }))
+                }
+            }
+
+            referenced
+        }
+        Some(Descriptor::Enum(_)) => Vec::new(),
+        None => path
+            .rsplit_once('.')
+            .and_then(|(message_path, oneof_name)| {
+                match package.and_then(|package| package.get_descriptor(message_path)) {
+                    Some(Descriptor::Message(message)) => message
+                        .get_oneofs()
+                        .into_iter()
+                        .find(|oneof| oneof.get_name() == oneof_name),
+                    _ => None,
+                }
+            })
+            .into_iter()
+            .flat_map(|oneof| oneof.get_fields())
+            .filter_map(|field| get_referenced_schema(field.get_field_type()))
+            .collect(),
+    }
+}
+
+/// Returns the schemas reached from the given ones, them included, following their references.
+pub fn get_reached_schemas(
+    packages: &BTreeMap<String, Package>,
+    options: &GeneratorOptions,
+    mut pending: Vec<(String, String)>,
+) -> BTreeSet<(String, String)> {
+    let mut schemas = BTreeSet::<(String, String)>::new();
+    while let Some(schema) = pending.pop() {
+        if !schemas.contains(&schema) {
+            pending.extend(get_referenced_schemas(packages, options, &schema));
+            schemas.insert(schema);
+        }
+    }
+
+    schemas
+}
+
+/// Returns the schemas a schema reaches that reach it back, itself included if it references
+/// itself. Schemas inline the schemas they reference, so these ones are referenced by name
+/// instead, otherwise building the schema would never end.
+pub fn get_recursive_schemas(
+    packages: &BTreeMap<String, Package>,
+    options: &GeneratorOptions,
+    schema: &(String, String),
+) -> BTreeSet<(String, String)> {
+    get_reached_schemas(
+        packages,
+        options,
+        get_referenced_schemas(packages, options, schema),
+    )
+    .into_iter()
+    .filter(|reached| {
+        get_reached_schemas(
+            packages,
+            options,
+            get_referenced_schemas(packages, options, reached),
+        )
+        .contains(schema)
+    })
+    .collect()
+}
+
+fn get_referenced_schema(field_type: FieldType) -> Option<(String, String)> {
+    match field_type {
+        FieldType::Object {
+            package,
+            descriptor,
+        }
+        | FieldType::Enum {
+            package,
+            descriptor,
+        } => Some((package, descriptor)),
+        FieldType::Map { value, .. } => get_referenced_schema(*value),
+        _ => None,
+    }
+}
@@ -0,0 +1,205 @@
+use crate::{
+    generator::Indent,
+    ident::to_relative_rust_path,
+    schema::{AdditionalProperties, ArraySchema, CompositeSchema, Literal, ObjectSchema, Schema},
+};
+
+/// Returns the lines building a schema with the utoipa builders. Descriptors are referenced
+/// relative to `current_package`.
+pub fn get_schema_component(
+    base_indent: usize,
+    current_package: &str,
+    schema: &Schema,
+) -> Vec<String> {
+    let i_00 = Indent(base_indent);
+
+    match schema {
+        Schema::Object(object) => get_object_component(base_indent, current_package, object),
+        Schema::Array(array) => get_array_component(base_indent, current_package, array),
+        Schema::OneOf(one_of) => get_composite_component(
+            base_indent,
+            current_package,
+            "utoipa::openapi::OneOfBuilder::new()",
+            one_of,
+        ),
+        Schema::AllOf(all_of) => get_composite_component(
+            base_indent,
+            current_package,
+            "utoipa::openapi::AllOfBuilder::new()",
+            all_of,
+        ),
+        Schema::Descriptor { package, path } => vec![format!(
+            "{i_00}<{} as utoipa::ToSchema>::schema().1",
+            to_relative_rust_path(current_package, package, path)
+        )],
+        Schema::Ref(schema_name) => vec![format!(
+            "{i_00}utoipa::openapi::Ref::from_schema_name({schema_name:?})"
+        )],
+    }
+}
+
+fn get_object_component(
+    base_indent: usize,
+    current_package: &str,
+    object: &ObjectSchema,
+) -> Vec<String> {
+    let i_00 = Indent(base_indent);
+    let i_04 = Indent(base_indent + 1);
+    let i_08 = Indent(base_indent + 2);
+
+    let mut component = vec![format!("{i_00}utoipa::openapi::ObjectBuilder::new()")];
+
+    if let Some(schema_type) = object.schema_type {
+        component.push(format!("{i_04}.schema_type({schema_type})"));
+    }
+    if let Some(known_format) = object.format {
+        component.push(format!(
+            "{i_04}.format(Some(utoipa::openapi::SchemaFormat::KnownFormat("
+        ));
+        component.push(format!("{i_08}{known_format}"));
+        component.push(format!("{i_04})))"));
+    }
+    if let Some(pattern) = object.pattern {
+        component.push(format!("{i_04}.pattern(Some(r\"{pattern}\"))"));
+    }
+    if let Some(enum_values) = &object.enum_values {
+        component.push(format!("{i_04}.enum_values(Some(vec!["));
+        enum_values.iter().for_each(|enum_value| {
+            component.push(format!("{i_08}{enum_value:?},"));
+        });
+        component.push(format!("{i_04}]))"));
+    }
+    for (property_name, property) in &object.properties {
+        component.push(format!("{i_04}.property("));
+        component.push(format!("{i_08}{property_name:?},"));
+        component.append(&mut get_schema_component(
+            base_indent + 2,
+            current_package,
+            property,
+        ));
+        component.push(format!("{i_04})"));
+    }
+    for property_name in &object.required {
+        component.push(format!("{i_04}.required({property_name:?})"));
+    }
+    match &object.additional_properties {
+        Some(AdditionalProperties::FreeForm) => {
+            component.push(format!("{i_04}.additional_properties(Some("));
+            component.push(format!(
+                "{i_08}utoipa::openapi::schema::AdditionalProperties::FreeForm(true)"
+            ));
+            component.push(format!("{i_04}))"));
+        }
+        Some(AdditionalProperties::Schema(schema)) => {
+            component.push(format!("{i_04}.additional_properties(Some("));
+            component.push(format!(
+                "{i_08}utoipa::openapi::RefOr::<utoipa::openapi::schema::Schema>::from("
+            ));
+            component.append(&mut get_schema_component(
+                base_indent + 3,
+                current_package,
+                schema,
+            ));
+            component.push(format!("{i_08})"));
+            component.push(format!("{i_04}))"));
+        }
+        None => {}
+    }
+
+    component.append(&mut get_annotations_component(
+        base_indent + 1,
+        object.nullable,
+        object.default.as_ref(),
+        object.deprecated,
+        object.description.as_deref(),
+    ));
+
+    component
+}
+
+fn get_array_component(
+    base_indent: usize,
+    current_package: &str,
+    array: &ArraySchema,
+) -> Vec<String> {
+    let i_00 = Indent(base_indent);
+
+    let mut component = vec![format!(
+        "{i_00}utoipa::openapi::ArrayBuilder::from(utoipa::openapi::Array::new("
+    )];
+    component.append(&mut get_schema_component(
+        base_indent + 1,
+        current_package,
+        &array.items,
+    ));
+    component.push(format!("{i_00}))"));
+
+    component.append(&mut get_annotations_component(
+        base_indent + 1,
+        array.nullable,
+        array.default.as_ref(),
+        array.deprecated,
+        array.description.as_deref(),
+    ));
+
+    component
+}
+
+fn get_composite_component(
+    base_indent: usize,
+    current_package: &str,
+    builder: &str,
+    composite: &CompositeSchema,
+) -> Vec<String> {
+    let i_00 = Indent(base_indent);
+    let i_04 = Indent(base_indent + 1);
+
+    let mut component = vec![format!("{i_00}{builder}")];
+    for item in &composite.items {
+        component.push(format!("{i_04}.item("));
+        component.append(&mut get_schema_component(
+            base_indent + 2,
+            current_package,
+            item,
+        ));
+        component.push(format!("{i_04})"));
+    }
+
+    component.append(&mut get_annotations_component(
+        base_indent + 1,
+        composite.nullable,
+        composite.default.as_ref(),
+        false,
+        composite.description.as_deref(),
+    ));
+
+    component
+}
+
+fn get_annotations_component(
+    base_indent: usize,
+    nullable: bool,
+    default: Option<&Literal>,
+    deprecated: bool,
+    description: Option<&str>,
+) -> Vec<String> {
+    let indent = Indent(base_indent);
+
+    let mut component = Vec::<String>::new();
+    if nullable {
+        component.push(format!("{indent}.nullable(true)"));
+    }
+    if let Some(default) = default {
+        component.push(format!("{indent}.default(Some(From::from({default})))"));
+    }
+    if deprecated {
+        component.push(format!(
+            "{indent}.deprecated(Some(utoipa::openapi::Deprecated::True))"
+        ));
+    }
+    if let Some(description) = description {
+        component.push(format!("{indent}.description(Some({description:?}))"));
+    }
+
+    component
+}
@@ -0,0 +1,50 @@
+use crate::{
+    descriptor::message_descriptor::well_known::WellKnownType,
+    generator::{message::get_field_scalar_schema, JsonMapping},
+    schema::{AdditionalProperties, KnownFormat, ObjectSchema, Schema, SchemaType},
+};
+
+/// Returns the canonical proto3 JSON schema of a well-known type.
+pub fn get_well_known_schema(well_known_type: WellKnownType, json_mapping: JsonMapping) -> Schema {
+    match well_known_type {
+        WellKnownType::Any => Schema::Object(ObjectSchema {
+            properties: vec![(
+                "@type".to_string(),
+                Schema::Object(ObjectSchema::typed(SchemaType::String)),
+            )],
+            required: vec!["@type".to_string()],
+            additional_properties: Some(AdditionalProperties::FreeForm),
+            ..Default::default()
+        }),
+        WellKnownType::Duration => Schema::Object(ObjectSchema {
+            pattern: Some(r"^-?[0-9]+(\.[0-9]{1,9})?s$"),
+            description: Some(
+                "Seconds with up to nine fractional digits, suffixed with `s`.".to_string(),
+            ),
+            ..ObjectSchema::typed(SchemaType::String)
+        }),
+        WellKnownType::Empty => Schema::Object(ObjectSchema::default()),
+        WellKnownType::FieldMask => Schema::Object(ObjectSchema {
+            description: Some("Comma separated field paths in lowerCamelCase.".to_string()),
+            ..ObjectSchema::typed(SchemaType::String)
+        }),
+        WellKnownType::ListValue => {
+            Schema::array(Schema::Object(ObjectSchema::typed(SchemaType::Value)))
+        }
+        WellKnownType::NullValue => {
+            Schema::Object(ObjectSchema::typed(SchemaType::Value)).nullable(true)
+        }
+        WellKnownType::Struct => Schema::Object(ObjectSchema {
+            additional_properties: Some(AdditionalProperties::FreeForm),
+            ..Default::default()
+        }),
+        WellKnownType::Timestamp => Schema::Object(ObjectSchema {
+            format: Some(KnownFormat::DateTime),
+            ..ObjectSchema::typed(SchemaType::String)
+        }),
+        WellKnownType::Value => Schema::Object(ObjectSchema::typed(SchemaType::Value)),
+        WellKnownType::Wrapper(scalar_type) => {
+            get_field_scalar_schema(scalar_type, json_mapping).nullable(true)
+        }
+    }
+}
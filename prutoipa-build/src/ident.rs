@@ -0,0 +1,181 @@
+//! Identifier conversions matching the ones used by `prost-build`, so generated code refers to the
+//! same Rust items `prost` emits.
+
+use heck::{ToSnakeCase, ToUpperCamelCase};
+
+/// Converts a `camelCase` or `SCREAMING_SNAKE_CASE` identifier to a `lower_snake` case Rust
+/// identifier, as `prost` does for modules and fields.
+pub fn to_snake(s: &str) -> String {
+    let mut ident = s.to_snake_case();
+
+    match ident.as_str() {
+        // 2015 strict keywords.
+        | "as" | "break" | "const" | "continue" | "else" | "enum" | "false"
+        | "fn" | "for" | "if" | "impl" | "in" | "let" | "loop" | "match" | "mod" | "move" | "mut"
+        | "pub" | "ref" | "return" | "static" | "struct" | "trait" | "true"
+        | "type" | "unsafe" | "use" | "where" | "while"
+        // 2018 strict keywords.
+        | "dyn"
+        // 2015 reserved keywords.
+        | "abstract" | "become" | "box" | "do" | "final" | "macro" | "override" | "priv" | "typeof"
+        | "unsized" | "virtual" | "yield"
+        // 2018 reserved keywords.
+        | "async" | "await" | "try" => ident.insert_str(0, "r#"),
+        // Keywords not supported as raw identifiers are suffixed with an underscore.
+        "self" | "super" | "extern" | "crate" => ident += "_",
+        _ => (),
+    }
+
+    ident
+}
+
+/// Converts a `snake_case` identifier to an `UpperCamel` case Rust type identifier, as `prost`
+/// does for messages and enums.
+pub fn to_upper_camel(s: &str) -> String {
+    let mut ident = s.to_upper_camel_case();
+
+    if ident == "Self" {
+        ident += "_";
+    }
+
+    ident
+}
+
+/// Returns the Rust path of a (possibly nested) descriptor relative to its package module.
+///
+/// `Outer.Inner` becomes `outer::Inner`, as `prost` places nested types at a module named after
+/// their parent message.
+pub fn to_rust_path(descriptor_path: &str) -> String {
+    let mut segments = descriptor_path.split('.').collect::<Vec<&str>>();
+    let name = segments.pop().unwrap_or_default();
+
+    segments
+        .into_iter()
+        .map(to_snake)
+        .chain(std::iter::once(to_upper_camel(name)))
+        .collect::<Vec<String>>()
+        .join("::")
+}
+
+/// Returns the Rust path of a descriptor of `target_package` relative to the module of
+/// `current_package`, walking up with `super` as many levels as needed.
+///
+/// `prost` generates one module per package segment, so `acme.billing.v1` lives at
+/// `acme::billing::v1`.
+pub fn to_relative_rust_path(
+    current_package: &str,
+    target_package: &str,
+    descriptor_path: &str,
+) -> String {
+    let current_modules = current_package.split('.').collect::<Vec<&str>>();
+    let target_modules = target_package.split('.').collect::<Vec<&str>>();
+
+    let common_modules = current_modules
+        .iter()
+        .zip(target_modules.iter())
+        .take_while(|(current, target)| current == target)
+        .count();
+
+    vec!["super".to_string(); current_modules.len() - common_modules]
+        .into_iter()
+        .chain(target_modules[common_modules..].iter().map(|m| to_snake(m)))
+        .chain(std::iter::once(to_rust_path(descriptor_path)))
+        .collect::<Vec<String>>()
+        .join("::")
+}
+
+/// Returns the schema name of a (possibly nested) descriptor, e.g. `pkg.Outer.Inner`.
+pub fn to_schema_name(package_name: &str, descriptor_path: &str) -> String {
+    std::iter::once(package_name.to_string())
+        .chain(descriptor_path.split('.').map(to_upper_camel))
+        .collect::<Vec<String>>()
+        .join(".")
+}
+
+/// Returns the proto3 JSON name of a field, as `protoc` computes it when `json_name` is not set:
+/// underscores are removed and the letter following them is capitalized.
+pub fn to_json_name(field_name: &str) -> String {
+    let mut capitalize_next = false;
+
+    field_name
+        .chars()
+        .filter_map(|c| match c {
+            '_' => {
+                capitalize_next = true;
+                None
+            }
+            c if capitalize_next => {
+                capitalize_next = false;
+                Some(c.to_ascii_uppercase())
+            }
+            c => Some(c),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rust_identifiers() {
+        assert_eq!(to_snake("FooBar"), "foo_bar");
+        assert_eq!(to_snake("Type"), "r#type");
+        assert_eq!(to_snake("Self"), "self_");
+        assert_eq!(to_upper_camel("GENDER"), "Gender");
+        assert_eq!(to_upper_camel("self"), "Self_");
+    }
+
+    #[test]
+    fn nested_paths() {
+        assert_eq!(to_rust_path("Person"), "Person");
+        assert_eq!(to_rust_path("Outer.Inner"), "outer::Inner");
+        assert_eq!(
+            to_rust_path("Outer.MiddleOne.INNER"),
+            "outer::middle_one::Inner"
+        );
+
+        assert_eq!(to_schema_name("pkg", "Outer.Inner"), "pkg.Outer.Inner");
+        assert_eq!(
+            to_schema_name("acme.billing.v1", "Invoice"),
+            "acme.billing.v1.Invoice"
+        );
+        assert_eq!(to_schema_name("people", "GENDER"), "people.Gender");
+    }
+
+    #[test]
+    fn relative_paths() {
+        assert_eq!(
+            to_relative_rust_path("pkg", "pkg", "Outer.Inner"),
+            "outer::Inner"
+        );
+        assert_eq!(
+            to_relative_rust_path("auth", "pagination", "Pagination"),
+            "super::pagination::Pagination"
+        );
+        assert_eq!(
+            to_relative_rust_path("acme.billing.v1", "acme.billing.v2", "Invoice"),
+            "super::v2::Invoice"
+        );
+        assert_eq!(
+            to_relative_rust_path("acme.billing.v1", "google.protobuf", "Timestamp"),
+            "super::super::super::google::protobuf::Timestamp"
+        );
+        assert_eq!(
+            to_relative_rust_path("acme.billing.v1", "acme", "Money"),
+            "super::super::Money"
+        );
+        assert_eq!(
+            to_relative_rust_path("acme", "acme.billing.v1", "Invoice.Line"),
+            "billing::v1::invoice::Line"
+        );
+    }
+
+    #[test]
+    fn json_names() {
+        assert_eq!(to_json_name("other_attribute"), "otherAttribute");
+        assert_eq!(to_json_name("otherAttribute"), "otherAttribute");
+        assert_eq!(to_json_name("address_line_1"), "addressLine1");
+        assert_eq!(to_json_name("_private"), "Private");
+    }
+}
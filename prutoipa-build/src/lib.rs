@@ -1,26 +1,75 @@
+#[cfg(feature = "compile")]
+mod compile;
 mod descriptor;
+mod diagnostics;
+#[cfg(feature = "document")]
+mod document;
 mod error;
 mod generator;
+mod ident;
 mod package;
 mod package_set;
+mod plugin;
+mod resolver;
+mod schema;
 mod syntax;
 
 use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs::File,
     io::{BufWriter, Write},
     path::PathBuf,
 };
 
-use descriptor::Descriptor;
-use error::PrutoipaBuildError;
-use generator::{enumeration::generate_enum, message::generate_message};
+use descriptor::{message_descriptor::well_known::WELL_KNOWN_PACKAGE, Descriptor};
+use diagnostics::get_presence_override_warnings;
+#[cfg(feature = "document")]
+use document::render_document;
+use generator::{
+    components::{generate_components, get_component_paths},
+    enumeration::generate_enum,
+    generate_header,
+    message::generate_message,
+    openapi::generate_openapi,
+    paths::generate_paths,
+    GeneratorOptions, OpenApiInfo,
+};
 use package_set::PackageSet;
 use prost_types::FileDescriptorSet;
 
+#[cfg(feature = "compile")]
+pub use compile::compile_protos;
+pub use diagnostics::Warning;
+#[cfg(feature = "document")]
+pub use document::{DocumentFormat, OpenApiVersion};
+pub use error::{ErrorLocation, PrutoipaBuildError};
+pub use generator::{
+    message::OneOfStyle, EnumRepresentation, JsonMapping, PresencePolicy, PropertyNaming,
+    PropertyPresence,
+};
+pub use plugin::generate_plugin_response;
+
+/// Generates utoipa implementations, and OpenAPI documents, from protobuf descriptors
 #[derive(Debug, Default)]
 pub struct Builder {
     out_dir: Option<PathBuf>,
     package_set: PackageSet,
-    generate_enum_values: bool,
+    generator_options: GeneratorOptions,
+    #[cfg(feature = "document")]
+    openapi_version: OpenApiVersion,
+    strict: bool,
+    warn_unannotated_methods: bool,
+    /// Packages to generate, every registered one if unset
+    generated_packages: Option<BTreeSet<String>>,
+}
+
+/// Output of [`Builder::generate`]
+#[derive(Debug)]
+pub struct Generated<W> {
+    /// Writers of the generated code, by package name
+    pub files: Vec<(String, W)>,
+    /// Constructs of the descriptors that the generated code does not document faithfully
+    pub warnings: Vec<Warning>,
 }
 
 impl Builder {
@@ -41,9 +90,104 @@ impl Builder {
         self
     }
 
-    /// Generate utoipa enum_values property at enums
+    /// Generate utoipa enum_values property with the value numbers at enums, names are always
+    /// generated when they are accepted
     pub fn generate_enum_values(&mut self) -> &mut Self {
-        self.generate_enum_values = true;
+        self.generator_options.generate_enum_values = true;
+        self
+    }
+
+    /// Configures how enums are represented, defaults to [`EnumRepresentation::String`]
+    pub fn enum_representation(&mut self, enum_representation: EnumRepresentation) -> &mut Self {
+        self.generator_options.enum_representation = enum_representation;
+        self
+    }
+
+    /// Configures how one-of groups are represented, defaults to [`OneOfStyle::Flattened`]
+    pub fn oneof_style(&mut self, oneof_style: OneOfStyle) -> &mut Self {
+        self.generator_options.oneof_style = oneof_style;
+        self
+    }
+
+    /// Configures how 64-bit integers are represented, defaults to [`JsonMapping::Canonical`]
+    pub fn json_mapping(&mut self, json_mapping: JsonMapping) -> &mut Self {
+        self.generator_options.json_mapping = json_mapping;
+        self
+    }
+
+    /// Configures how property names are derived from field names, defaults to
+    /// [`PropertyNaming::JsonName`]
+    pub fn property_naming(&mut self, property_naming: PropertyNaming) -> &mut Self {
+        self.generator_options.property_naming = property_naming;
+        self
+    }
+
+    /// Configures which properties are required or nullable, defaults to
+    /// [`PresencePolicy::Strict`]
+    pub fn presence_policy(&mut self, presence_policy: PresencePolicy) -> &mut Self {
+        self.generator_options.presence_policy = presence_policy;
+        self
+    }
+
+    /// Overrides the presence of the property of a field, given by its full name like
+    /// `package.Message.field`. Overrides of fields that are not defined are warned about
+    pub fn field_presence<S>(&mut self, field: S, presence: PropertyPresence) -> &mut Self
+    where
+        S: Into<String>,
+    {
+        let field = field.into();
+        self.generator_options
+            .presence_overrides
+            .insert(field.trim_start_matches('.').to_string(), presence);
+        self
+    }
+
+    /// Document the zero value of proto3 fields without explicit presence as their default
+    pub fn document_implicit_defaults(&mut self) -> &mut Self {
+        self.generator_options.implicit_defaults = true;
+        self
+    }
+
+    /// Keep the Markdown of proto comments at descriptions, otherwise lines are unwrapped
+    pub fn markdown_comments(&mut self) -> &mut Self {
+        self.generator_options.markdown_comments = true;
+        self
+    }
+
+    /// Generate an `openapi` function at every package, returning an OpenAPI document with the
+    /// paths of its services, the schemas they reach and a tag per service. The description of
+    /// the document comes from the comments of the `package` statements
+    pub fn openapi<T, V>(&mut self, title: T, version: V) -> &mut Self
+    where
+        T: Into<String>,
+        V: Into<String>,
+    {
+        self.generator_options.openapi_info = Some(OpenApiInfo {
+            title: title.into(),
+            version: version.into(),
+        });
+        self
+    }
+
+    /// Configures the OpenAPI version of the standalone documents, defaults to
+    /// [`OpenApiVersion::V3_0`]. The generated code always documents OpenAPI 3.0
+    #[cfg(feature = "document")]
+    pub fn openapi_version(&mut self, openapi_version: OpenApiVersion) -> &mut Self {
+        self.openapi_version = openapi_version;
+        self
+    }
+
+    /// Fail with the first warning instead of reporting the warnings
+    pub fn strict(&mut self) -> &mut Self {
+        self.strict = true;
+        self
+    }
+
+    /// Warn about every method without a `google.api.http` annotation, which is not documented.
+    /// Methods registered with [`Builder::register_descriptors`] are never reported, as their
+    /// annotations were dropped
+    pub fn warn_unannotated_methods(&mut self) -> &mut Self {
+        self.warn_unannotated_methods = true;
         self
     }
 
@@ -59,6 +203,9 @@ impl Builder {
     }
 
     /// Register a `FileDescriptorSet` with this `Builder`
+    ///
+    /// `prost_types` drops the `google.api.http` annotations of the methods while decoding, so
+    /// services are only documented if registered with [`Builder::register_descriptors_encoded`].
     pub fn register_descriptors(
         &mut self,
         fds: FileDescriptorSet,
@@ -68,6 +215,25 @@ impl Builder {
         Ok(self)
     }
 
+    /// Compile `.proto` files with a pure-Rust parser, no `protoc` required, and register them
+    /// with this `Builder`. Imports are searched at the include directories
+    ///
+    /// Comments and `google.api.http` annotations are kept, as if registered with
+    /// [`Builder::register_descriptors_encoded`] from `protoc --include_source_info`.
+    #[cfg(feature = "compile")]
+    pub fn compile_protos<P>(
+        &mut self,
+        protos: &[P],
+        includes: &[P],
+    ) -> Result<&mut Self, error::PrutoipaBuildError>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        let fds_encoded = compile::compile_protos(protos, includes)?;
+
+        self.register_descriptors_encoded(&fds_encoded)
+    }
+
     fn get_out_dir(&self) -> Result<PathBuf, PrutoipaBuildError> {
         if let Some(out_dir) = self.out_dir.clone() {
             Ok(out_dir)
@@ -78,12 +244,16 @@ impl Builder {
         }
     }
 
-    pub fn build(&mut self) -> Result<(), PrutoipaBuildError> {
+    /// Returns a factory of the `<package>.<extension>` files at the output directory
+    fn get_file_factory(
+        &self,
+        extension: String,
+    ) -> Result<impl FnMut(String) -> std::io::Result<BufWriter<File>>, PrutoipaBuildError> {
         let mut output = self.get_out_dir()?;
         output.push("DUMMY_FILENAME");
 
-        let write_factory = move |package_name: String| {
-            output.set_file_name(format!("{}.utoipa.rs", package_name));
+        Ok(move |package_name: String| {
+            output.set_file_name(format!("{}.{}", package_name, extension));
 
             let file = std::fs::OpenOptions::new()
                 .write(true)
@@ -92,28 +262,73 @@ impl Builder {
                 .open(&output)?;
 
             Ok(BufWriter::new(file))
-        };
+        })
+    }
 
-        let writers = self.generate(write_factory)?;
-        for (_, mut writer) in writers {
-            writer.flush()?;
-        }
+    pub fn build(&mut self) -> Result<(), PrutoipaBuildError> {
+        let generated = self.generate(self.get_file_factory("utoipa.rs".to_string())?)?;
+
+        flush_generated(generated)
+    }
+
+    /// Writes the OpenAPI document of every package, the same one its generated `openapi`
+    /// function returns, as `<package>.openapi.json` or `<package>.openapi.yaml` files at the
+    /// output directory. Unlike the generated code, no Rust crate has to be built to get them
+    #[cfg(feature = "document")]
+    pub fn build_documents(&mut self, format: DocumentFormat) -> Result<(), PrutoipaBuildError> {
+        let write_factory = self.get_file_factory(format!("openapi.{}", format.get_extension()))?;
+        let generated = self.generate_documents(format, write_factory)?;
+
+        flush_generated(generated)
+    }
+
+    #[cfg(feature = "document")]
+    pub fn generate_documents<W: Write, F: FnMut(String) -> std::io::Result<W>>(
+        &self,
+        format: DocumentFormat,
+        mut write_factory: F,
+    ) -> Result<Generated<W>, PrutoipaBuildError> {
+        let (packages, warnings) = self.get_packages()?;
+
+        let files = packages
+            .iter()
+            .filter(|(package_name, _)| self.is_generated(package_name))
+            .map(|(package_name, package)| {
+                let document = render_document(
+                    package,
+                    &packages,
+                    &self.generator_options,
+                    format,
+                    self.openapi_version,
+                )?;
+
+                let mut writer = write_factory(package_name.clone())?;
+                writer.write_all(document.as_bytes())?;
 
-        Ok(())
+                Ok((package_name.clone(), writer))
+            })
+            .collect::<Result<Vec<(String, W)>, PrutoipaBuildError>>()?;
+
+        Ok(Generated { files, warnings })
     }
 
     pub fn generate<W: Write, F: FnMut(String) -> std::io::Result<W>>(
         &self,
         mut write_factory: F,
-    ) -> Result<Vec<(String, W)>, PrutoipaBuildError> {
-        self.package_set
-            .get_packages()
-            .into_iter()
-            .map(|(package_name, mut package)| {
+    ) -> Result<Generated<W>, PrutoipaBuildError> {
+        let (packages, warnings) = self.get_packages()?;
+
+        let files = packages
+            .iter()
+            .filter(|(package_name, _)| self.is_generated(package_name))
+            .map(|(package_name, package)| {
                 let mut writer = write_factory(package_name.clone())?;
+                generate_header(&mut writer, package.get_files())?;
+
+                let descriptors = package.get_descriptors();
+                let component_paths = get_component_paths(&descriptors, &self.generator_options);
 
-                package
-                    .get_descriptors()
+                descriptors
                     .into_iter()
                     .map(|(descriptor_name, descriptor)| match descriptor {
                         Descriptor::Message(message) => generate_message(
@@ -121,31 +336,101 @@ impl Builder {
                             package.get_name(),
                             descriptor_name,
                             message,
+                            &packages,
+                            &self.generator_options,
                         ),
                         Descriptor::Enum(enum_descriptor) => generate_enum(
                             &mut writer,
                             package.get_name(),
                             descriptor_name,
                             enum_descriptor,
-                            self.generate_enum_values,
+                            &self.generator_options,
                         ),
                     })
                     .collect::<Result<Vec<()>, PrutoipaBuildError>>()?;
 
-                Ok((package_name, writer))
+                generate_components(&mut writer, package.get_name(), component_paths)?;
+                generate_paths(&mut writer, package, &packages, &self.generator_options)?;
+                if let Some(info) = &self.generator_options.openapi_info {
+                    generate_openapi(
+                        &mut writer,
+                        package,
+                        &packages,
+                        info,
+                        &self.generator_options,
+                    )?;
+                }
+
+                Ok((package_name.clone(), writer))
             })
-            .collect::<Result<Vec<(String, W)>, PrutoipaBuildError>>()
+            .collect::<Result<Vec<(String, W)>, PrutoipaBuildError>>()?;
+
+        Ok(Generated { files, warnings })
+    }
+
+    /// Returns whether a package is generated. Well-known types are provided by `prost_types`
+    fn is_generated(&self, package_name: &str) -> bool {
+        let requested = match &self.generated_packages {
+            Some(package_names) => package_names.contains(package_name),
+            None => true,
+        };
+
+        package_name != WELL_KNOWN_PACKAGE && requested
     }
+
+    /// Returns the resolved packages with the warnings about them, failing with the first one in
+    /// strict mode. Warnings about packages that are not generated, like the well-known types or
+    /// the imports of a plugin request, are left out.
+    fn get_packages(
+        &self,
+    ) -> Result<(BTreeMap<String, package::Package>, Vec<Warning>), PrutoipaBuildError> {
+        let packages = self.package_set.get_resolved_packages()?;
+
+        let mut warnings = self.package_set.get_warnings();
+        if self.warn_unannotated_methods {
+            warnings.extend(self.package_set.get_unannotated_method_warnings());
+        }
+        warnings.retain(|warning| {
+            packages.contains_key(&warning.location.package)
+                && self.is_generated(&warning.location.package)
+        });
+        // Overrides are not bound to a package, a misspelled one is never applied
+        warnings.extend(get_presence_override_warnings(
+            &packages,
+            &self.generator_options,
+        ));
+        if let (true, Some(warning)) = (self.strict, warnings.first()) {
+            return Err(PrutoipaBuildError::StrictWarning(warning.clone().into()));
+        }
+
+        Ok((packages, warnings))
+    }
+}
+
+/// Reports the warnings to Cargo and flushes the written files
+fn flush_generated(generated: Generated<BufWriter<File>>) -> Result<(), PrutoipaBuildError> {
+    for warning in generated.warnings {
+        println!("cargo:warning={warning}");
+    }
+    for (_, mut writer) in generated.files {
+        writer.flush()?;
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    // use super::*;
+    use super::*;
+    use descriptor::service_descriptor::http_rule::{
+        AnnotatedFile, AnnotatedMethod, AnnotatedMethodOptions, AnnotatedService, HttpRule,
+    };
     use prost::Message;
     use prost_types::{
         field_descriptor_proto::{Label, Type},
+        source_code_info::Location,
         DescriptorProto, EnumDescriptorProto, EnumValueDescriptorProto, FieldDescriptorProto,
-        FileDescriptorProto, FileDescriptorSet,
+        FileDescriptorProto, MessageOptions, OneofDescriptorProto, SourceCodeInfo,
     };
 
     pub fn get_file_descriptor_proto() -> FileDescriptorProto {
@@ -202,4 +487,851 @@ mod tests {
 
         fds_encoded
     }
+
+    fn get_recursive_fds_encoded() -> Vec<u8> {
+        let file_descriptor_proto = get_file_descriptor_proto();
+        let mut person = file_descriptor_proto.message_type[0].clone();
+        person.field.push(FieldDescriptorProto {
+            r#type: Some(Type::Message.into()),
+            type_name: Some(".people.Person".to_string()),
+            name: Some("parent".to_string()),
+            number: Some(3),
+            label: Some(Label::Optional.into()),
+            ..Default::default()
+        });
+
+        get_fds_encoded(vec![FileDescriptorProto {
+            message_type: vec![person],
+            ..file_descriptor_proto
+        }])
+    }
+
+    #[test]
+    fn strict_mode() {
+        let fds_encoded = get_fds_encoded(vec![get_file_descriptor_proto()]);
+        let mut builder = Builder::new();
+        builder
+            .strict()
+            .register_descriptors_encoded(fds_encoded.as_slice())
+            .unwrap();
+
+        assert!(builder.generate(|_| Ok(Vec::<u8>::new())).is_ok());
+
+        let file_descriptor_proto = get_file_descriptor_proto();
+        let extension = FieldDescriptorProto {
+            r#type: Some(Type::String.into()),
+            name: Some("nickname".to_string()),
+            number: Some(100),
+            label: Some(Label::Optional.into()),
+            extendee: Some(".people.Person".to_string()),
+            ..Default::default()
+        };
+
+        // Warnings about packages that are not generated do not fail
+        let well_known_file_descriptor_proto = FileDescriptorProto {
+            name: Some("google/protobuf/nickname.proto".to_string()),
+            package: Some(WELL_KNOWN_PACKAGE.to_string()),
+            dependency: vec!["person.proto".to_string()],
+            message_type: Vec::new(),
+            enum_type: Vec::new(),
+            extension: vec![extension.clone()],
+            ..file_descriptor_proto.clone()
+        };
+        let mut builder = Builder::new();
+        builder
+            .strict()
+            .register_descriptors_encoded(
+                get_fds_encoded(vec![
+                    file_descriptor_proto.clone(),
+                    well_known_file_descriptor_proto,
+                ])
+                .as_slice(),
+            )
+            .unwrap();
+
+        assert!(builder.generate(|_| Ok(Vec::<u8>::new())).is_ok());
+
+        let mut builder = Builder::new();
+        builder
+            .strict()
+            .register_descriptors_encoded(
+                get_fds_encoded(vec![FileDescriptorProto {
+                    extension: vec![extension],
+                    ..file_descriptor_proto
+                }])
+                .as_slice(),
+            )
+            .unwrap();
+
+        let err = builder.generate(|_| Ok(Vec::<u8>::new())).err().unwrap();
+
+        assert_eq!(
+            err.to_string(),
+            "Extensions are not documented, 'nickname' extends 'people.Person' at \
+             'people.nickname' (person.proto) (strict mode)."
+        );
+    }
+
+    #[test]
+    fn presence_override_warnings() {
+        let mut builder = Builder::new();
+        builder
+            .field_presence("people.Person.id", PropertyPresence::Nullable)
+            .field_presence("people.Person.identifier", PropertyPresence::Nullable)
+            .register_descriptors_encoded(
+                get_fds_encoded(vec![get_file_descriptor_proto()]).as_slice(),
+            )
+            .unwrap();
+
+        let generated = builder.generate(|_| Ok(Vec::<u8>::new())).unwrap();
+
+        let expected_warnings = vec![Warning::new(
+            ErrorLocation {
+                path: "people.Person.identifier".to_string(),
+                ..Default::default()
+            },
+            "Presence override of a field that is not defined".to_string(),
+        )];
+
+        assert_eq!(generated.warnings, expected_warnings);
+        assert_eq!(
+            generated.warnings[0].to_string(),
+            "Presence override of a field that is not defined at 'people.Person.identifier'"
+        );
+    }
+
+    #[test]
+    fn components_function() {
+        let mut builder = Builder::new();
+        builder
+            .register_descriptors_encoded(
+                get_fds_encoded(vec![get_file_descriptor_proto()]).as_slice(),
+            )
+            .unwrap();
+
+        let generated = builder.generate(|_| Ok(Vec::<u8>::new())).unwrap();
+        let (package_name, code) = &generated.files[0];
+        let code = String::from_utf8(code.clone()).unwrap();
+
+        let expected_code = [
+            "/// Returns the schemas of all the messages and enums of the `people` package.",
+            "pub fn components() -> utoipa::openapi::Components {",
+            "    utoipa::openapi::ComponentsBuilder::new()",
+            "        .schema_from::<Gender>()",
+            "        .schema_from::<Person>()",
+            "        .build()",
+            "}",
+            "",
+        ]
+        .join("\n");
+
+        assert_eq!(package_name, "people");
+        assert!(code.contains(&expected_code));
+    }
+
+    /// Encodes files along with services annotated with `google.api.http`, which `prost_types`
+    /// can not represent.
+    pub fn get_annotated_fds_encoded(
+        files: Vec<(FileDescriptorProto, Vec<AnnotatedService>)>,
+    ) -> Vec<u8> {
+        #[derive(Clone, PartialEq, Message)]
+        struct EncodedFileDescriptorSet {
+            #[prost(bytes = "vec", repeated, tag = "1")]
+            file: Vec<Vec<u8>>,
+        }
+
+        let file = files
+            .into_iter()
+            .map(|(file, service)| {
+                let mut file_encoded = file.encode_to_vec();
+                AnnotatedFile { service }.encode(&mut file_encoded).unwrap();
+
+                file_encoded
+            })
+            .collect();
+
+        EncodedFileDescriptorSet { file }.encode_to_vec()
+    }
+
+    fn get_people_service(http_rule: HttpRule) -> AnnotatedService {
+        AnnotatedService {
+            name: Some("People".to_string()),
+            method: vec![AnnotatedMethod {
+                name: Some("GetPerson".to_string()),
+                input_type: Some(".people.Person".to_string()),
+                output_type: Some(".people.Person".to_string()),
+                options: Some(AnnotatedMethodOptions {
+                    http: Some(http_rule),
+                }),
+            }],
+        }
+    }
+
+    #[test]
+    fn paths_function() {
+        let file_descriptor_proto = FileDescriptorProto {
+            source_code_info: Some(SourceCodeInfo {
+                location: vec![Location {
+                    path: vec![6, 0, 2, 0],
+                    leading_comments: Some(" Gets a person.\n\n Found by id.\n".to_string()),
+                    ..Default::default()
+                }],
+            }),
+            ..get_file_descriptor_proto()
+        };
+        let http_rule = HttpRule {
+            get: Some("/v1/people/{id}".to_string()),
+            ..Default::default()
+        };
+
+        let mut builder = Builder::new();
+        builder
+            .register_descriptors_encoded(
+                get_annotated_fds_encoded(vec![(
+                    file_descriptor_proto,
+                    vec![get_people_service(http_rule)],
+                )])
+                .as_slice(),
+            )
+            .unwrap();
+
+        let generated = builder.generate(|_| Ok(Vec::<u8>::new())).unwrap();
+        let code = String::from_utf8(generated.files[0].1.clone()).unwrap();
+
+        let expected_code = [
+            "pub fn paths() -> utoipa::openapi::Paths {",
+            "    utoipa::openapi::PathsBuilder::new()",
+            "        .path(",
+            "            \"/v1/people/{id}\",",
+            "            utoipa::openapi::path::PathItemBuilder::new()",
+            "                .operation(",
+            "                    utoipa::openapi::PathItemType::Get,",
+            "                    utoipa::openapi::path::OperationBuilder::new()",
+            "                        .operation_id(Some(\"People_GetPerson\"))",
+            "                        .tag(\"People\")",
+            "                        .summary(Some(\"Gets a person.\"))",
+            "                        .description(Some(\"Found by id.\"))",
+            "                        .parameter(",
+            "                            utoipa::openapi::path::ParameterBuilder::new()",
+            "                                .name(\"id\")",
+            "                                .parameter_in(utoipa::openapi::path::ParameterIn::Path)",
+            "                                .required(utoipa::openapi::Required::True)",
+            "                                .schema(Some(",
+            "                                    utoipa::openapi::ObjectBuilder::new()",
+            "                                        .schema_type(utoipa::openapi::SchemaType::Integer)",
+            "                                        .format(Some(utoipa::openapi::SchemaFormat::KnownFormat(",
+            "                                            utoipa::openapi::KnownFormat::Int32",
+            "                                        )))",
+            "                                )),",
+            "                        )",
+            "                        .parameter(",
+            "                            utoipa::openapi::path::ParameterBuilder::new()",
+            "                                .name(\"otherAttribute\")",
+            "                                .parameter_in(utoipa::openapi::path::ParameterIn::Query)",
+            "                                .schema(Some(",
+            "                                    utoipa::openapi::ObjectBuilder::new()",
+            "                                        .schema_type(utoipa::openapi::SchemaType::String)",
+            "                                )),",
+            "                        )",
+            "                        .response(",
+            "                            \"200\",",
+            "                            utoipa::openapi::ResponseBuilder::new()",
+            "                                .description(\"A successful response.\")",
+            "                                .content(",
+            "                                    \"application/json\",",
+            "                                    utoipa::openapi::ContentBuilder::new()",
+            "                                        .schema(",
+            "                                            <Person as utoipa::ToSchema>::schema().1",
+            "                                        )",
+            "                                        .build(),",
+            "                                )",
+            "                        )",
+            "                )",
+            "                .build(),",
+            "        )",
+            "        .build()",
+            "}",
+            "",
+        ]
+        .join("\n");
+
+        assert!(code.ends_with(&expected_code));
+    }
+
+    #[test]
+    fn openapi_function() {
+        let file_descriptor_proto = FileDescriptorProto {
+            source_code_info: Some(SourceCodeInfo {
+                location: vec![
+                    Location {
+                        path: vec![2],
+                        leading_comments: Some(" People directory.\n".to_string()),
+                        ..Default::default()
+                    },
+                    Location {
+                        path: vec![6, 0],
+                        leading_comments: Some(" Manages people.\n".to_string()),
+                        ..Default::default()
+                    },
+                ],
+            }),
+            ..get_file_descriptor_proto()
+        };
+        let http_rule = HttpRule {
+            get: Some("/v1/people/{id}".to_string()),
+            ..Default::default()
+        };
+
+        let mut builder = Builder::new();
+        builder
+            .register_descriptors_encoded(
+                get_annotated_fds_encoded(vec![(
+                    file_descriptor_proto,
+                    vec![get_people_service(http_rule)],
+                )])
+                .as_slice(),
+            )
+            .unwrap()
+            .openapi("People API", "1.0.0");
+
+        let generated = builder.generate(|_| Ok(Vec::<u8>::new())).unwrap();
+        let code = String::from_utf8(generated.files[0].1.clone()).unwrap();
+
+        let expected_code = [
+            "/// Returns the OpenAPI document of the `people` package.",
+            "pub fn openapi() -> utoipa::openapi::OpenApi {",
+            "    utoipa::openapi::OpenApiBuilder::new()",
+            "        .info(",
+            "            utoipa::openapi::InfoBuilder::new()",
+            "                .title(\"People API\")",
+            "                .version(\"1.0.0\")",
+            "                .description(Some(\"People directory.\"))",
+            "                .build(),",
+            "        )",
+            "        .paths(paths())",
+            "        .components(Some(",
+            "            utoipa::openapi::ComponentsBuilder::new()",
+            "                .schema_from::<Gender>()",
+            "                .schema_from::<Person>()",
+            "                .build(),",
+            "        ))",
+            "        .tags(Some([",
+            "            utoipa::openapi::tag::TagBuilder::new()",
+            "                .name(\"People\")",
+            "                .description(Some(\"Manages people.\"))",
+            "                .build(),",
+            "        ]))",
+            "        .build()",
+            "}",
+            "",
+        ]
+        .join("\n");
+
+        assert!(code.ends_with(&expected_code));
+    }
+
+    #[test]
+    fn unannotated_method_warning() {
+        let mut people_service = get_people_service(HttpRule::default());
+        people_service.method[0].options = None;
+        let fds_encoded =
+            get_annotated_fds_encoded(vec![(get_file_descriptor_proto(), vec![people_service])]);
+
+        // gRPC-only methods are only reported on demand, so strict mode accepts them
+        let mut builder = Builder::new();
+        builder
+            .strict()
+            .register_descriptors_encoded(fds_encoded.as_slice())
+            .unwrap();
+
+        assert!(builder.generate(|_| Ok(Vec::<u8>::new())).is_ok());
+
+        let mut builder = Builder::new();
+        builder
+            .warn_unannotated_methods()
+            .register_descriptors_encoded(fds_encoded.as_slice())
+            .unwrap();
+
+        let generated = builder.generate(|_| Ok(Vec::<u8>::new())).unwrap();
+
+        let expected_warnings = vec![Warning::new(
+            ErrorLocation {
+                file: "person.proto".to_string(),
+                package: "people".to_string(),
+                path: "people.People.GetPerson".to_string(),
+                position: None,
+            },
+            "Methods without a google.api.http annotation are not documented".to_string(),
+        )];
+
+        assert_eq!(generated.warnings, expected_warnings);
+    }
+
+    #[test]
+    fn http_rule_with_unknown_field() {
+        let http_rule = HttpRule {
+            post: Some("/v1/people".to_string()),
+            body: "address".to_string(),
+            ..Default::default()
+        };
+
+        let mut builder = Builder::new();
+        builder
+            .register_descriptors_encoded(
+                get_annotated_fds_encoded(vec![(
+                    get_file_descriptor_proto(),
+                    vec![get_people_service(http_rule)],
+                )])
+                .as_slice(),
+            )
+            .unwrap();
+
+        let err = builder.generate(|_| Ok(Vec::<u8>::new())).err().unwrap();
+
+        let expected_location = ErrorLocation {
+            file: "person.proto".to_string(),
+            package: "people".to_string(),
+            path: "people.People.GetPerson".to_string(),
+            position: None,
+        };
+
+        assert!(matches!(
+            &err,
+            PrutoipaBuildError::InvalidHttpRule { reason, .. }
+                if reason == "field 'address' is not defined"
+        ));
+        assert_eq!(err.get_location(), Some(&expected_location));
+    }
+
+    #[test]
+    fn malformed_descriptors_do_not_panic() {
+        let map_entry = DescriptorProto {
+            name: Some("EntryEntry".to_string()),
+            field: vec![
+                FieldDescriptorProto {
+                    r#type: Some(Type::String.into()),
+                    name: Some("key".to_string()),
+                    number: Some(1),
+                    label: Some(Label::Optional.into()),
+                    ..Default::default()
+                },
+                FieldDescriptorProto {
+                    r#type: Some(Type::Message.into()),
+                    type_name: Some(".people.Person.EntryEntry".to_string()),
+                    name: Some("value".to_string()),
+                    number: Some(2),
+                    label: Some(Label::Optional.into()),
+                    ..Default::default()
+                },
+            ],
+            options: Some(MessageOptions {
+                map_entry: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let types = [None, Some(0), Some(19)]
+            .into_iter()
+            .chain((1..=18).map(Some));
+        let labels = [None, Some(0), Some(1), Some(2), Some(3)];
+        let type_names = [
+            None,
+            Some(".people.Person"),
+            Some(".people.GENDER"),
+            Some(".people.Missing"),
+            Some(".people.Person.EntryEntry"),
+            Some(".google.protobuf.Api"),
+        ];
+
+        for r#type in types {
+            for label in labels {
+                for type_name in type_names {
+                    for oneof_index in [None, Some(0), Some(-1)] {
+                        for default_value in [None, Some("1")] {
+                            for syntax in ["proto2", "proto3"] {
+                                let file_descriptor_proto = get_file_descriptor_proto();
+                                let mut person = file_descriptor_proto.message_type[0].clone();
+                                person.nested_type.push(map_entry.clone());
+                                person.oneof_decl.push(OneofDescriptorProto {
+                                    name: Some("choice".to_string()),
+                                    ..Default::default()
+                                });
+                                person.field.push(FieldDescriptorProto {
+                                    r#type,
+                                    type_name: type_name.map(str::to_string),
+                                    name: Some("malformed".to_string()),
+                                    number: Some(3),
+                                    label,
+                                    oneof_index,
+                                    proto3_optional: Some(oneof_index == Some(0)),
+                                    default_value: default_value.map(str::to_string),
+                                    ..Default::default()
+                                });
+
+                                let fds_encoded = get_fds_encoded(vec![FileDescriptorProto {
+                                    syntax: Some(syntax.to_string()),
+                                    message_type: vec![person],
+                                    ..file_descriptor_proto
+                                }]);
+
+                                let mut builder = Builder::new();
+                                if builder
+                                    .register_descriptors_encoded(fds_encoded.as_slice())
+                                    .is_ok()
+                                {
+                                    let _ = builder.generate(|_| Ok(Vec::<u8>::new()));
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "document")]
+    #[test]
+    fn json_document() {
+        let http_rule = HttpRule {
+            get: Some("/v1/people/{id}".to_string()),
+            ..Default::default()
+        };
+
+        let mut builder = Builder::new();
+        builder
+            .register_descriptors_encoded(
+                get_annotated_fds_encoded(vec![(
+                    get_file_descriptor_proto(),
+                    vec![get_people_service(http_rule)],
+                )])
+                .as_slice(),
+            )
+            .unwrap()
+            .openapi("People API", "1.0.0");
+
+        let generated = builder
+            .generate_documents(DocumentFormat::Json, |_| Ok(Vec::<u8>::new()))
+            .unwrap();
+        let (package_name, document) = &generated.files[0];
+        let document = serde_json::from_slice::<serde_json::Value>(document).unwrap();
+
+        let person_schema = serde_json::json!({
+            "type": "object",
+            "required": ["id", "otherAttribute"],
+            "properties": {
+                "id": { "type": "integer", "format": "int32" },
+                "otherAttribute": { "type": "string" }
+            }
+        });
+        let expected_document = serde_json::json!({
+            "openapi": "3.0.3",
+            "info": { "title": "People API", "version": "1.0.0" },
+            "paths": {
+                "/v1/people/{id}": {
+                    "get": {
+                        "tags": ["People"],
+                        "operationId": "People_GetPerson",
+                        "parameters": [
+                            {
+                                "name": "id",
+                                "in": "path",
+                                "required": true,
+                                "schema": { "type": "integer", "format": "int32" }
+                            },
+                            {
+                                "name": "otherAttribute",
+                                "in": "query",
+                                "required": false,
+                                "schema": { "type": "string" }
+                            }
+                        ],
+                        "responses": {
+                            "200": {
+                                "description": "A successful response.",
+                                "content": {
+                                    "application/json": { "schema": person_schema }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "people.Gender": {
+                        "type": "string",
+                        "description": "Values:\n\n0 = MALE\n\n1 = FEMALE",
+                        "enum": ["MALE", "FEMALE"]
+                    },
+                    "people.Person": person_schema
+                }
+            },
+            "tags": [{ "name": "People" }]
+        });
+
+        assert_eq!(package_name, "people");
+        assert_eq!(document, expected_document);
+    }
+
+    #[cfg(feature = "document")]
+    #[test]
+    fn openapi_3_1_document() {
+        let file_descriptor_proto = get_file_descriptor_proto();
+        let mut person = file_descriptor_proto.message_type[0].clone();
+        person.field.push(FieldDescriptorProto {
+            r#type: Some(Type::Bool.into()),
+            name: Some("nullable".to_string()),
+            number: Some(3),
+            label: Some(Label::Optional.into()),
+            ..Default::default()
+        });
+
+        let mut builder = Builder::new();
+        builder
+            .openapi_version(OpenApiVersion::V3_1)
+            .field_presence("people.Person.id", PropertyPresence::Nullable)
+            .register_descriptors_encoded(
+                get_fds_encoded(vec![FileDescriptorProto {
+                    message_type: vec![person],
+                    ..file_descriptor_proto
+                }])
+                .as_slice(),
+            )
+            .unwrap();
+
+        let generated = builder
+            .generate_documents(DocumentFormat::Json, |_| Ok(Vec::<u8>::new()))
+            .unwrap();
+        let document = serde_json::from_slice::<serde_json::Value>(&generated.files[0].1).unwrap();
+
+        // Properties named like the keyword are kept
+        let expected_person_schema = serde_json::json!({
+            "type": "object",
+            "required": ["otherAttribute", "nullable"],
+            "properties": {
+                "id": { "type": ["integer", "null"], "format": "int32" },
+                "otherAttribute": { "type": "string" },
+                "nullable": { "type": "boolean" }
+            }
+        });
+
+        assert_eq!(document["openapi"], "3.1.0");
+        assert_eq!(
+            document["components"]["schemas"]["people.Person"],
+            expected_person_schema
+        );
+    }
+
+    #[cfg(feature = "document")]
+    #[test]
+    fn yaml_document() {
+        let mut builder = Builder::new();
+        builder
+            .openapi_version(OpenApiVersion::V3_1)
+            .register_descriptors_encoded(get_recursive_fds_encoded().as_slice())
+            .unwrap();
+
+        let generated = builder
+            .generate_documents(DocumentFormat::Yaml, |_| Ok(Vec::<u8>::new()))
+            .unwrap();
+        let document = String::from_utf8(generated.files[0].1.clone()).unwrap();
+
+        // Recursive schemas reference themselves instead of being inlined
+        let expected_document = [
+            "openapi: 3.1.0",
+            "info:",
+            "  title: people",
+            "  version: 0.0.0",
+            "paths: {}",
+            "components:",
+            "  schemas:",
+            "    people.Gender:",
+            "      type: string",
+            "      description: |-",
+            "        Values:",
+            "",
+            "        0 = MALE",
+            "",
+            "        1 = FEMALE",
+            "      enum:",
+            "      - MALE",
+            "      - FEMALE",
+            "    people.Person:",
+            "      type: object",
+            "      required:",
+            "      - id",
+            "      - otherAttribute",
+            "      properties:",
+            "        id:",
+            "          type: integer",
+            "          format: int32",
+            "        otherAttribute:",
+            "          type: string",
+            "        parent:",
+            "          $ref: '#/components/schemas/people.Person'",
+            "",
+        ]
+        .join("\n");
+
+        assert_eq!(document, expected_document);
+    }
+
+    fn get_code_generator_request_encoded(
+        parameter: &str,
+        file_to_generate: Vec<String>,
+        files: Vec<(FileDescriptorProto, Vec<AnnotatedService>)>,
+    ) -> Vec<u8> {
+        #[derive(Clone, PartialEq, Message)]
+        struct EncodedCodeGeneratorRequest {
+            #[prost(string, repeated, tag = "1")]
+            file_to_generate: Vec<String>,
+            #[prost(string, optional, tag = "2")]
+            parameter: Option<String>,
+            #[prost(bytes = "vec", repeated, tag = "15")]
+            proto_file: Vec<Vec<u8>>,
+        }
+
+        let proto_file = files
+            .into_iter()
+            .map(|(file, service)| {
+                let mut file_encoded = file.encode_to_vec();
+                AnnotatedFile { service }.encode(&mut file_encoded).unwrap();
+
+                file_encoded
+            })
+            .collect();
+
+        EncodedCodeGeneratorRequest {
+            file_to_generate,
+            parameter: Some(parameter.to_string()),
+            proto_file,
+        }
+        .encode_to_vec()
+    }
+
+    #[cfg(feature = "document")]
+    #[test]
+    fn plugin_response() {
+        let http_rule = HttpRule {
+            get: Some("/v1/people/{id}".to_string()),
+            ..Default::default()
+        };
+        let imported_file = FileDescriptorProto {
+            syntax: Some("proto3".to_string()),
+            package: Some("imported".to_string()),
+            name: Some("imported.proto".to_string()),
+            message_type: vec![DescriptorProto {
+                name: Some("Imported".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let (response, warnings) = generate_plugin_response(&get_code_generator_request_encoded(
+            "format=rust,format=json,openapi=People API:1.0.0",
+            vec!["person.proto".to_string()],
+            vec![
+                (imported_file, Vec::new()),
+                (
+                    get_file_descriptor_proto(),
+                    vec![get_people_service(http_rule)],
+                ),
+            ],
+        ));
+
+        let file_names = response
+            .file
+            .iter()
+            .map(|file| file.name().to_string())
+            .collect::<Vec<String>>();
+        let document =
+            serde_json::from_str::<serde_json::Value>(response.file[1].content()).unwrap();
+
+        assert_eq!(response.error, None);
+        assert_eq!(response.supported_features, Some(1));
+        assert!(warnings.is_empty());
+        assert_eq!(file_names, vec!["people.utoipa.rs", "people.openapi.json"]);
+        assert!(response.file[0]
+            .content()
+            .contains("pub fn openapi() -> utoipa::openapi::OpenApi {"));
+        assert_eq!(document["info"]["title"], "People API");
+        assert_eq!(document["info"]["version"], "1.0.0");
+        assert!(document["paths"]["/v1/people/{id}"]["get"].is_object());
+    }
+
+    #[test]
+    fn plugin_imported_warnings() {
+        let file_descriptor_proto = get_file_descriptor_proto();
+        let imported_file = FileDescriptorProto {
+            syntax: Some("proto3".to_string()),
+            package: Some("imported".to_string()),
+            name: Some("imported.proto".to_string()),
+            dependency: vec!["person.proto".to_string()],
+            extension: vec![FieldDescriptorProto {
+                r#type: Some(Type::String.into()),
+                name: Some("nickname".to_string()),
+                number: Some(100),
+                label: Some(Label::Optional.into()),
+                extendee: Some(".people.Person".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let files = vec![
+            (file_descriptor_proto, Vec::new()),
+            (imported_file, Vec::new()),
+        ];
+
+        // Warnings about imported files are neither reported nor fail in strict mode
+        let (response, warnings) = generate_plugin_response(&get_code_generator_request_encoded(
+            "strict",
+            vec!["person.proto".to_string()],
+            files.clone(),
+        ));
+
+        assert_eq!(response.error, None);
+        assert!(warnings.is_empty());
+
+        let (response, warnings) = generate_plugin_response(&get_code_generator_request_encoded(
+            "",
+            vec!["imported.proto".to_string()],
+            files,
+        ));
+
+        assert_eq!(response.error, None);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].location.package, "imported");
+    }
+
+    #[test]
+    fn plugin_invalid_parameter() {
+        let (response, _) = generate_plugin_response(&get_code_generator_request_encoded(
+            "format=xml",
+            vec!["person.proto".to_string()],
+            vec![(get_file_descriptor_proto(), Vec::new())],
+        ));
+
+        assert!(response.file.is_empty());
+        assert_eq!(
+            response.error(),
+            "Invalid plugin parameter 'format=xml': expected rust, json or yaml."
+        );
+    }
+
+    #[cfg(not(feature = "document"))]
+    #[test]
+    fn plugin_document_without_feature() {
+        let (response, _) = generate_plugin_response(&get_code_generator_request_encoded(
+            "format=json",
+            vec!["person.proto".to_string()],
+            vec![(get_file_descriptor_proto(), Vec::new())],
+        ));
+
+        assert!(response.file.is_empty());
+        assert_eq!(
+            response.error(),
+            "Invalid plugin parameter 'format=json': requires the document feature."
+        );
+    }
 }
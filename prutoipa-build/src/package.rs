@@ -1,60 +1,214 @@
-use heck::ToUpperCamelCase;
 use prost_types::FileDescriptorProto;
-use std::collections::{btree_map::Entry, BTreeMap};
+use std::collections::BTreeMap;
 
-use crate::{descriptor::Descriptor, error::PrutoipaBuildError, syntax::Syntax};
+use crate::{
+    descriptor::{
+        comments::{Comments, FileComments, FILE_PACKAGE},
+        service_descriptor::ServiceDescriptor,
+        Descriptor,
+    },
+    error::{ErrorLocation, PrutoipaBuildError},
+    resolver::Resolver,
+    syntax::Syntax,
+};
 
 #[derive(Debug, Clone)]
 pub struct Package {
-    syntax: Syntax,
     name: String,
+    files: Vec<PackageFile>,
     descriptors: BTreeMap<String, Descriptor>,
+    services: BTreeMap<String, ServiceDescriptor>,
+    descriptor_files: BTreeMap<String, String>,
 }
 
-impl Package {
-    pub fn new(file: FileDescriptorProto) -> Result<Self, PrutoipaBuildError> {
-        let syntax = Syntax::get(file.syntax.as_deref())?;
-        let name = file.package.ok_or(PrutoipaBuildError::InvalidData(
-            "Expected package name.".to_string(),
-        ))?;
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageFile {
+    name: String,
+    syntax: Syntax,
+    comments: Comments,
+}
 
-        Ok(Self {
-            syntax,
+impl Package {
+    pub fn new(name: String) -> Self {
+        Self {
             name,
+            files: Vec::<PackageFile>::new(),
             descriptors: BTreeMap::<String, Descriptor>::new(),
-        })
+            services: BTreeMap::<String, ServiceDescriptor>::new(),
+            descriptor_files: BTreeMap::<String, String>::new(),
+        }
     }
 
     //
-    pub fn get_syntax(&mut self) -> Syntax {
-        self.syntax
+    pub fn get_name(&self) -> String {
+        self.name.clone()
     }
 
-    pub fn get_name(&mut self) -> String {
-        self.name.clone()
+    pub fn get_files(&self) -> Vec<PackageFile> {
+        self.files.clone()
     }
 
-    pub fn get_descriptors(&mut self) -> BTreeMap<String, Descriptor> {
+    pub fn get_descriptors(&self) -> BTreeMap<String, Descriptor> {
         self.descriptors.clone()
     }
 
+    pub fn get_descriptor(&self, name: &str) -> Option<Descriptor> {
+        self.descriptors.get(name).cloned()
+    }
+
+    pub fn get_services(&self) -> BTreeMap<String, ServiceDescriptor> {
+        self.services.clone()
+    }
+
+    pub fn get_descriptor_file(&self, name: &str) -> Option<String> {
+        self.descriptor_files.get(name).cloned()
+    }
+
     //
+    pub fn has_file(&self, name: &str) -> bool {
+        self.files.iter().any(|file| file.name == name)
+    }
+
+    pub fn register_file(&mut self, file: PackageFile) {
+        self.files.push(file);
+    }
+
+    pub fn resolve(self, resolver: &Resolver) -> Result<Self, PrutoipaBuildError> {
+        let descriptors = self
+            .descriptors
+            .into_iter()
+            .map(|(name, descriptor)| {
+                let error_location = ErrorLocation {
+                    file: self
+                        .descriptor_files
+                        .get(&name)
+                        .cloned()
+                        .unwrap_or_default(),
+                    package: self.name.clone(),
+                    path: format!("{}.{name}", self.name),
+                    position: None,
+                };
+                let descriptor = match descriptor {
+                    Descriptor::Message(message) => Descriptor::Message(
+                        message
+                            .resolve(resolver)
+                            .map_err(|err| err.at(&error_location))?,
+                    ),
+                    Descriptor::Enum(enum_descriptor) => Descriptor::Enum(enum_descriptor),
+                };
+
+                Ok((name, descriptor))
+            })
+            .collect::<Result<BTreeMap<String, Descriptor>, PrutoipaBuildError>>()?;
+        let services = self
+            .services
+            .into_iter()
+            .map(|(name, service)| {
+                let error_location = ErrorLocation {
+                    file: self
+                        .descriptor_files
+                        .get(&name)
+                        .cloned()
+                        .unwrap_or_default(),
+                    package: self.name.clone(),
+                    path: format!("{}.{name}", self.name),
+                    position: None,
+                };
+                let service = service
+                    .resolve(resolver)
+                    .map_err(|err| err.at(&error_location))?;
+
+                Ok((name, service))
+            })
+            .collect::<Result<BTreeMap<String, ServiceDescriptor>, PrutoipaBuildError>>()?;
+
+        Ok(Self {
+            descriptors,
+            services,
+            ..self
+        })
+    }
+
     pub fn register_descriptor(
         &mut self,
+        file: &PackageFile,
         name: String,
         descriptor: Descriptor,
     ) -> Result<(), PrutoipaBuildError> {
-        let descriptor_name = name.to_upper_camel_case();
-
-        match self.descriptors.entry(descriptor_name) {
-            Entry::Occupied(o) => Err(PrutoipaBuildError::InvalidData(format!(
-                "Descriptor '{}' registered more than once at the same package.",
-                o.key()
-            ))),
-            Entry::Vacant(v) => {
-                v.insert(descriptor);
-                Ok(())
-            }
+        self.check_vacant(file, &name)?;
+        self.descriptor_files
+            .insert(name.clone(), file.name.clone());
+        self.descriptors.insert(name, descriptor);
+
+        Ok(())
+    }
+
+    pub fn register_service(
+        &mut self,
+        file: &PackageFile,
+        name: String,
+        service: ServiceDescriptor,
+    ) -> Result<(), PrutoipaBuildError> {
+        self.check_vacant(file, &name)?;
+        self.descriptor_files
+            .insert(name.clone(), file.name.clone());
+        self.services.insert(name, service);
+
+        Ok(())
+    }
+
+    /// Messages, enums and services share the same namespace.
+    fn check_vacant(&self, file: &PackageFile, name: &str) -> Result<(), PrutoipaBuildError> {
+        match self.descriptor_files.get(name) {
+            Some(previous_file) => Err(PrutoipaBuildError::DuplicateDescriptor {
+                location: ErrorLocation {
+                    file: file.name.clone(),
+                    package: self.name.clone(),
+                    path: format!("{}.{name}", self.name),
+                    position: None,
+                }
+                .into(),
+                previous_file: previous_file.clone(),
+            }),
+            None => Ok(()),
         }
     }
 }
+
+impl PackageFile {
+    pub fn new(
+        file: &FileDescriptorProto,
+        file_comments: &FileComments,
+    ) -> Result<Self, PrutoipaBuildError> {
+        let name = file.name.clone().ok_or(PrutoipaBuildError::MissingName {
+            location: ErrorLocation::default().into(),
+            kind: "file",
+        })?;
+        let syntax = Syntax::get(file.syntax.as_deref()).map_err(|err| {
+            err.at(&ErrorLocation {
+                file: name.clone(),
+                ..Default::default()
+            })
+        })?;
+
+        Ok(Self {
+            name,
+            syntax,
+            comments: file_comments.get_comments(&[FILE_PACKAGE]),
+        })
+    }
+
+    //
+    pub fn get_name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn get_syntax(&self) -> Syntax {
+        self.syntax
+    }
+
+    /// Returns the comments of the `package` statement of the file.
+    pub fn get_comments(&self) -> Comments {
+        self.comments.clone()
+    }
+}
@@ -1,16 +1,37 @@
-use prost_types::{DescriptorProto, EnumDescriptorProto, FileDescriptorProto, FileDescriptorSet};
+use prost_types::{
+    compiler::CodeGeneratorRequest, DescriptorProto, EnumDescriptorProto, FieldDescriptorProto,
+    FileDescriptorProto, FileDescriptorSet, ServiceDescriptorProto,
+};
 use std::collections::BTreeMap;
 
 use crate::{
     descriptor::Descriptor,
-    descriptor::{enum_descriptor::EnumDescriptor, message_descriptor::MessageDescriptor},
-    error::PrutoipaBuildError,
-    package::Package,
+    descriptor::{
+        comments::{
+            get_child_path, FileComments, FILE_ENUM_TYPE, FILE_EXTENSION, FILE_MESSAGE_TYPE,
+            FILE_SERVICE, MESSAGE_ENUM_TYPE, MESSAGE_EXTENSION, MESSAGE_NESTED_TYPE,
+        },
+        enum_descriptor::EnumDescriptor,
+        message_descriptor::{well_known::WELL_KNOWN_PACKAGE, MessageDescriptor},
+        service_descriptor::{
+            http_rule::{
+                AnnotatedCodeGeneratorRequest, AnnotatedFile, AnnotatedFileDescriptorSet,
+                AnnotatedService,
+            },
+            ServiceDescriptor,
+        },
+    },
+    diagnostics::Warning,
+    error::{ErrorLocation, PrutoipaBuildError},
+    package::{Package, PackageFile},
+    resolver::Resolver,
 };
 
 #[derive(Debug, Clone, Default)]
 pub struct PackageSet {
     packages: BTreeMap<String, Package>,
+    warnings: Vec<Warning>,
+    unannotated_method_warnings: Vec<Warning>,
 }
 
 impl PackageSet {
@@ -19,128 +40,486 @@ impl PackageSet {
         fds_encoded: &[u8],
     ) -> Result<(), PrutoipaBuildError> {
         let file_descriptor_set: FileDescriptorSet = prost::Message::decode(fds_encoded)
-            .map_err(|e| PrutoipaBuildError::InvalidDescriptorSet(e))?;
+            .map_err(PrutoipaBuildError::InvalidDescriptorSet)?;
+        let annotated_file_descriptor_set: AnnotatedFileDescriptorSet =
+            prost::Message::decode(fds_encoded)
+                .map_err(PrutoipaBuildError::InvalidDescriptorSet)?;
 
-        self.register_file_descriptor_set(file_descriptor_set)
+        self.register_files(file_descriptor_set, annotated_file_descriptor_set.file)
     }
 
+    /// Registers the files of an encoded `CodeGeneratorRequest`, the input of `protoc` plugins,
+    /// returning the request.
+    pub fn register_code_generator_request_encoded(
+        &mut self,
+        request_encoded: &[u8],
+    ) -> Result<CodeGeneratorRequest, PrutoipaBuildError> {
+        let request: CodeGeneratorRequest = prost::Message::decode(request_encoded)
+            .map_err(PrutoipaBuildError::InvalidDescriptorSet)?;
+        let annotated_request: AnnotatedCodeGeneratorRequest =
+            prost::Message::decode(request_encoded)
+                .map_err(PrutoipaBuildError::InvalidDescriptorSet)?;
+
+        self.register_files(
+            FileDescriptorSet {
+                file: request.proto_file.clone(),
+            },
+            annotated_request.proto_file,
+        )?;
+
+        Ok(request)
+    }
+
+    /// Registers a decoded `FileDescriptorSet`, the `google.api.http` annotations of its methods
+    /// were dropped while decoding it.
     pub fn register_file_descriptor_set(
         &mut self,
         file_descriptor_set: FileDescriptorSet,
     ) -> Result<(), PrutoipaBuildError> {
-        file_descriptor_set
-            .file
+        self.register_files(file_descriptor_set, Vec::new())
+    }
+
+    /// Returns the warnings about the registered descriptors that are not documented.
+    pub fn get_warnings(&self) -> Vec<Warning> {
+        self.warnings.clone()
+    }
+
+    /// Returns the warnings about the methods without a `google.api.http` annotation. Methods of
+    /// descriptor sets whose annotations were dropped while decoding them are not reported.
+    pub fn get_unannotated_method_warnings(&self) -> Vec<Warning> {
+        self.unannotated_method_warnings.clone()
+    }
+
+    /// Returns the packages with every type reference resolved, failing on dangling references.
+    pub fn get_resolved_packages(&self) -> Result<BTreeMap<String, Package>, PrutoipaBuildError> {
+        let resolver = Resolver::new(&self.packages);
+
+        self.packages
+            .clone()
             .into_iter()
-            .map(|file| self.register_file_descriptor_proto(file))
+            // Well-known types are provided by `prost_types`, the descriptors of the package,
+            // like the ones of `google/protobuf/descriptor.proto`, are not generated
+            .filter(|(package_name, _)| package_name != WELL_KNOWN_PACKAGE)
+            .map(|(package_name, package)| Ok((package_name, package.resolve(&resolver)?)))
             .collect()
     }
 
-    pub fn get_packages(&self) -> BTreeMap<String, Package> {
-        self.packages.clone()
+    fn register_files(
+        &mut self,
+        file_descriptor_set: FileDescriptorSet,
+        annotated_files: Vec<AnnotatedFile>,
+    ) -> Result<(), PrutoipaBuildError> {
+        let mut annotated_files = annotated_files.into_iter();
+
+        file_descriptor_set
+            .file
+            .into_iter()
+            .map(|file| self.register_file_descriptor_proto(file, annotated_files.next()))
+            .collect::<Result<Vec<()>, PrutoipaBuildError>>()?;
+
+        Ok(())
     }
 
     fn register_file_descriptor_proto(
         &mut self,
         file: FileDescriptorProto,
+        annotated_file: Option<AnnotatedFile>,
     ) -> Result<(), PrutoipaBuildError> {
-        let mut package = Package::new(file.clone())?;
-        let package_name = package.get_name();
+        let package_name = file
+            .package
+            .clone()
+            .ok_or_else(|| PrutoipaBuildError::MissingName {
+                location: ErrorLocation {
+                    file: file.name().to_string(),
+                    ..Default::default()
+                }
+                .into(),
+                kind: "package",
+            })?;
+        let file_comments = FileComments::new(file.source_code_info.as_ref());
+        let package_file = PackageFile::new(&file, &file_comments)?;
 
-        match self.packages.contains_key(&package_name) {
-            true => Err(PrutoipaBuildError::InvalidData(format!(
-                "Package '{}' already defined.",
-                package_name
-            ))),
-            false => {
-                file.message_type
-                    .into_iter()
-                    .map(|descriptor| self.register_message(&mut package, descriptor))
-                    .collect::<Result<(), PrutoipaBuildError>>()?;
-
-                file.enum_type
-                    .into_iter()
-                    .map(|descriptor| self.register_enum(&mut package, descriptor))
-                    .collect::<Result<(), PrutoipaBuildError>>()?;
+        // The same file may come from more than one descriptor set
+        if self
+            .packages
+            .get(&package_name)
+            .is_some_and(|package| package.has_file(&package_file.get_name()))
+        {
+            return Ok(());
+        }
+
+        // The package and the warnings are only updated once the whole file is registered
+        let mut package = self
+            .packages
+            .get(&package_name)
+            .cloned()
+            .unwrap_or_else(|| Package::new(package_name.clone()));
+        let warning_counts = (self.warnings.len(), self.unannotated_method_warnings.len());
 
+        match self.register_file_descriptors(
+            &mut package,
+            package_file,
+            &file_comments,
+            file,
+            annotated_file,
+        ) {
+            Ok(()) => {
                 self.packages.insert(package_name, package);
 
                 Ok(())
             }
+            Err(err) => {
+                self.warnings.truncate(warning_counts.0);
+                self.unannotated_method_warnings.truncate(warning_counts.1);
+
+                Err(err)
+            }
         }
     }
 
+    fn register_file_descriptors(
+        &mut self,
+        package: &mut Package,
+        package_file: PackageFile,
+        file_comments: &FileComments,
+        file: FileDescriptorProto,
+        annotated_file: Option<AnnotatedFile>,
+    ) -> Result<(), PrutoipaBuildError> {
+        let file_location = ErrorLocation {
+            file: package_file.get_name(),
+            package: package.get_name(),
+            path: package.get_name(),
+            position: None,
+        };
+
+        self.register_extensions(
+            &file_location,
+            file_comments,
+            &[],
+            FILE_EXTENSION,
+            &file.extension,
+        );
+
+        file.message_type
+            .into_iter()
+            .enumerate()
+            .map(|(idx, descriptor)| {
+                self.register_message(
+                    package,
+                    &package_file,
+                    file_comments,
+                    None,
+                    vec![FILE_MESSAGE_TYPE, idx as i32],
+                    descriptor,
+                )
+            })
+            .collect::<Result<Vec<()>, PrutoipaBuildError>>()?;
+
+        file.enum_type
+            .into_iter()
+            .enumerate()
+            .map(|(idx, descriptor)| {
+                self.register_enum(
+                    package,
+                    &package_file,
+                    file_comments,
+                    None,
+                    vec![FILE_ENUM_TYPE, idx as i32],
+                    descriptor,
+                )
+            })
+            .collect::<Result<Vec<()>, PrutoipaBuildError>>()?;
+
+        let mut annotated_services = annotated_file
+            .map(|annotated_file| annotated_file.service)
+            .unwrap_or_default()
+            .into_iter();
+        file.service
+            .into_iter()
+            .enumerate()
+            .map(|(idx, descriptor)| {
+                self.register_service(
+                    package,
+                    &package_file,
+                    file_comments,
+                    vec![FILE_SERVICE, idx as i32],
+                    descriptor,
+                    annotated_services.next(),
+                )
+            })
+            .collect::<Result<Vec<()>, PrutoipaBuildError>>()?;
+
+        package.register_file(package_file);
+
+        Ok(())
+    }
+
     fn register_message(
         &mut self,
         package: &mut Package,
+        package_file: &PackageFile,
+        file_comments: &FileComments,
+        parent_path: Option<&str>,
+        location: Vec<i32>,
         descriptor: DescriptorProto,
     ) -> Result<(), PrutoipaBuildError> {
+        let mut error_location =
+            get_error_location(package, package_file, file_comments, parent_path, &location);
+        let name = descriptor
+            .name
+            .clone()
+            .ok_or_else(|| PrutoipaBuildError::MissingName {
+                location: error_location.clone().into(),
+                kind: "message",
+            })?;
+        let path = get_descriptor_path(parent_path, name);
+        error_location.path = format!("{}.{path}", package.get_name());
+
+        self.register_extensions(
+            &error_location,
+            file_comments,
+            &location,
+            MESSAGE_EXTENSION,
+            &descriptor.extension,
+        );
+
+        // Map entries are synthetic messages, prost does not generate them.
+        if descriptor
+            .options
+            .as_ref()
+            .and_then(|options| options.map_entry)
+            .unwrap_or(false)
+        {
+            return Ok(());
+        }
+
+        descriptor
+            .nested_type
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(idx, child_descriptor)| {
+                self.register_message(
+                    package,
+                    package_file,
+                    file_comments,
+                    Some(&path),
+                    get_child_path(&location, MESSAGE_NESTED_TYPE, idx),
+                    child_descriptor,
+                )
+            })
+            .collect::<Result<Vec<()>, PrutoipaBuildError>>()?;
+
+        descriptor
+            .enum_type
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(idx, child_descriptor)| {
+                self.register_enum(
+                    package,
+                    package_file,
+                    file_comments,
+                    Some(&path),
+                    get_child_path(&location, MESSAGE_ENUM_TYPE, idx),
+                    child_descriptor,
+                )
+            })
+            .collect::<Result<Vec<()>, PrutoipaBuildError>>()?;
+
+        let message_descriptor = MessageDescriptor::new(
+            package_file.get_syntax(),
+            package.get_name(),
+            path.clone(),
+            descriptor,
+            file_comments,
+            &location,
+        )
+        .map_err(|err| err.at(&error_location))?;
+        package
+            .register_descriptor(package_file, path, Descriptor::Message(message_descriptor))
+            .map_err(|err| err.at(&error_location))
+    }
+
+    fn register_service(
+        &mut self,
+        package: &mut Package,
+        package_file: &PackageFile,
+        file_comments: &FileComments,
+        location: Vec<i32>,
+        descriptor: ServiceDescriptorProto,
+        annotated: Option<AnnotatedService>,
+    ) -> Result<(), PrutoipaBuildError> {
+        let mut error_location =
+            get_error_location(package, package_file, file_comments, None, &location);
         let name = descriptor
             .name
             .clone()
-            .ok_or(PrutoipaBuildError::InvalidData(
-                "Expected message name.".to_string(),
-            ))?;
-
-        // TODO: Check how to do it.
-        // descriptor
-        //     .nested_type
-        //     .into_iter()
-        //     .map(|child_descriptor| self.register_message(syntax, package, child_descriptor))
-        //     .collect::<Result<(), PrutoipaBuildError>>()?;
-
-        // descriptor
-        //     .enum_type
-        //     .into_iter()
-        //     .map(|child_descriptor| self.register_enum(package, child_descriptor))
-        //     .collect::<Result<(), PrutoipaBuildError>>()?;
-
-        let syntax = package.get_syntax();
-        package.register_descriptor(
-            name,
-            Descriptor::Message(MessageDescriptor::new(syntax, descriptor)?),
+            .ok_or_else(|| PrutoipaBuildError::MissingName {
+                location: error_location.clone().into(),
+                kind: "service",
+            })?;
+        error_location.path = format!("{}.{name}", package.get_name());
+
+        let annotations_dropped = annotated.is_none();
+        let service_descriptor = ServiceDescriptor::new(
+            package.get_name(),
+            name.clone(),
+            descriptor,
+            annotated,
+            file_comments,
+            &location,
         )
+        .map_err(|err| err.at(&error_location))?;
+
+        // Only methods transcoded to HTTP can be documented, unless the annotations were dropped
+        for method in service_descriptor.get_methods() {
+            if !annotations_dropped && method.get_http_bindings().is_empty() {
+                self.unannotated_method_warnings.push(Warning::new(
+                    ErrorLocation {
+                        file: error_location.file.clone(),
+                        package: error_location.package.clone(),
+                        ..method.get_error_location()
+                    },
+                    "Methods without a google.api.http annotation are not documented".to_string(),
+                ));
+            }
+        }
+
+        package
+            .register_service(package_file, name, service_descriptor)
+            .map_err(|err| err.at(&error_location))
+    }
+
+    /// Extensions are not generated by `prost`, so they are only reported.
+    fn register_extensions(
+        &mut self,
+        parent_error_location: &ErrorLocation,
+        file_comments: &FileComments,
+        parent_location: &[i32],
+        field_number: i32,
+        extensions: &[FieldDescriptorProto],
+    ) {
+        for (idx, extension) in extensions.iter().enumerate() {
+            // Custom options, like `google.api.http`, are not part of the messages
+            let extendee = extension.extendee().trim_start_matches('.');
+            if extendee.starts_with("google.protobuf.") && extendee.ends_with("Options") {
+                continue;
+            }
+
+            let location = get_child_path(parent_location, field_number, idx);
+
+            self.warnings.push(Warning::new(
+                ErrorLocation {
+                    path: format!("{}.{}", parent_error_location.path, extension.name()),
+                    position: file_comments.get_position(&location),
+                    ..parent_error_location.clone()
+                },
+                format!(
+                    "Extensions are not documented, '{}' extends '{}'",
+                    extension.name(),
+                    extendee
+                ),
+            ));
+        }
     }
 
     fn register_enum(
         &mut self,
         package: &mut Package,
+        package_file: &PackageFile,
+        file_comments: &FileComments,
+        parent_path: Option<&str>,
+        location: Vec<i32>,
         descriptor: EnumDescriptorProto,
     ) -> Result<(), PrutoipaBuildError> {
-        let name = descriptor.name.ok_or(PrutoipaBuildError::InvalidData(
-            "Expected enum name.".to_string(),
-        ))?;
+        let error_location =
+            get_error_location(package, package_file, file_comments, parent_path, &location);
+        let name = descriptor
+            .name
+            .clone()
+            .ok_or_else(|| PrutoipaBuildError::MissingName {
+                location: error_location.clone().into(),
+                kind: "enum",
+            })?;
+
+        let path = get_descriptor_path(parent_path, name);
 
-        package.register_descriptor(
-            name,
-            Descriptor::Enum(EnumDescriptor::new(descriptor.value)),
+        let enum_descriptor = EnumDescriptor::new(
+            package.get_name(),
+            path.clone(),
+            descriptor,
+            file_comments,
+            &location,
         )
+        .map_err(|err| err.at(&error_location))?;
+        package
+            .register_descriptor(package_file, path, Descriptor::Enum(enum_descriptor))
+            .map_err(|err| err.at(&error_location))
+    }
+}
+
+/// Returns the location of a descriptor for errors, the path is the one of its parent until
+/// the name of the descriptor is known.
+fn get_error_location(
+    package: &Package,
+    package_file: &PackageFile,
+    file_comments: &FileComments,
+    parent_path: Option<&str>,
+    location: &[i32],
+) -> ErrorLocation {
+    ErrorLocation {
+        file: package_file.get_name(),
+        package: package.get_name(),
+        path: match parent_path {
+            Some(parent_path) => format!("{}.{parent_path}", package.get_name()),
+            None => package.get_name(),
+        },
+        position: file_comments.get_position(location),
+    }
+}
+
+fn get_descriptor_path(parent_path: Option<&str>, name: String) -> String {
+    match parent_path {
+        Some(parent_path) => format!("{parent_path}.{name}"),
+        None => name,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use prost_types::{DescriptorProto, EnumDescriptorProto, FileDescriptorProto};
+    use prost_types::{
+        field_descriptor_proto::{Label, Type},
+        source_code_info::Location,
+        DescriptorProto, EnumDescriptorProto, EnumOptions, EnumValueOptions, FieldDescriptorProto,
+        FieldOptions, FileDescriptorProto, MessageOptions, MethodDescriptorProto,
+        OneofDescriptorProto, ServiceDescriptorProto, SourceCodeInfo,
+    };
 
     use crate::{
-        descriptor::message_descriptor::field::{Field, FieldType, ScalarType},
+        descriptor::message_descriptor::{
+            default_value::DefaultValue,
+            field::{Field, FieldModifier, FieldType, ScalarType},
+            well_known::WellKnownType,
+        },
         syntax::Syntax,
         tests::{get_fds_encoded, get_file_descriptor_proto},
     };
 
-    #[test]
-    fn package_set_simple() {
-        fn get_field(descriptors: BTreeMap<String, Descriptor>, name: String) -> Option<Field> {
-            if let Descriptor::Message(message_descriptor) = descriptors.get("Person").unwrap() {
-                message_descriptor
-                    .get_fields()
-                    .into_iter()
-                    .find(|field| field.get_name() == name)
-            } else {
-                None
-            }
+    fn get_person_field(descriptors: BTreeMap<String, Descriptor>, name: &str) -> Option<Field> {
+        if let Descriptor::Message(message_descriptor) = descriptors.get("Person").unwrap() {
+            message_descriptor
+                .get_fields()
+                .into_iter()
+                .find(|field| field.get_name() == name)
+        } else {
+            None
         }
+    }
 
+    #[test]
+    fn package_set_simple() {
         let fds_encoded = get_fds_encoded(vec![get_file_descriptor_proto()]);
 
         let mut package_set = PackageSet::default();
@@ -148,45 +527,821 @@ mod tests {
             .register_file_descriptor_set_encoded(fds_encoded.as_slice())
             .unwrap();
 
-        let mut package = package_set.packages.get("people").unwrap().to_owned();
+        let package = package_set.packages.get("people").unwrap().to_owned();
         let package_name = package.get_name();
-        let package_syntax = package.get_syntax();
+        let package_files = package.get_files();
 
         assert_eq!(package_name, "people");
-        assert_eq!(package_syntax, Syntax::Proto3);
+        assert_eq!(package_files.len(), 1);
+        assert_eq!(package_files[0].get_name(), "person.proto");
+        assert_eq!(package_files[0].get_syntax(), Syntax::Proto3);
 
-        let field = get_field(package.get_descriptors(), "id".to_string()).unwrap();
+        let field = get_person_field(package.get_descriptors(), "id").unwrap();
         let field_name = field.get_name();
         let field_type = field.get_field_type();
 
         assert_eq!(field_name, "id");
         assert_eq!(field_type, FieldType::Scalar(ScalarType::I32));
 
-        let field = get_field(package.get_descriptors(), "other_attribute".to_string()).unwrap();
+        let field = get_person_field(package.get_descriptors(), "otherAttribute").unwrap();
         let field_name = field.get_name();
+        let field_json_name = field.get_json_name();
         let field_type = field.get_field_type();
 
-        assert_eq!(field_name, "other_attribute");
+        assert_eq!(field_name, "otherAttribute");
+        assert_eq!(field_json_name, "otherAttribute");
         assert_eq!(field_type, FieldType::Scalar(ScalarType::String));
     }
 
     #[test]
-    fn package_defined_twice() {
+    fn nested_descriptors() {
+        let file_descriptor_proto = get_file_descriptor_proto();
+        let person = file_descriptor_proto.message_type[0].clone();
+        let fds_encoded = get_fds_encoded(vec![FileDescriptorProto {
+            message_type: vec![DescriptorProto {
+                field: vec![FieldDescriptorProto {
+                    r#type: Some(Type::Message.into()),
+                    type_name: Some(".people.Person.Address.Street".to_string()),
+                    name: Some("street".to_string()),
+                    number: Some(3),
+                    label: Some(Label::Optional.into()),
+                    ..Default::default()
+                }],
+                nested_type: vec![
+                    DescriptorProto {
+                        name: Some("Address".to_string()),
+                        nested_type: vec![DescriptorProto {
+                            name: Some("Street".to_string()),
+                            ..Default::default()
+                        }],
+                        enum_type: file_descriptor_proto.enum_type.clone(),
+                        ..Default::default()
+                    },
+                    DescriptorProto {
+                        name: Some("TagsEntry".to_string()),
+                        options: Some(MessageOptions {
+                            map_entry: Some(true),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    },
+                ],
+                ..person
+            }],
+            ..file_descriptor_proto
+        }]);
+
+        let mut package_set = PackageSet::default();
+        package_set
+            .register_file_descriptor_set_encoded(fds_encoded.as_slice())
+            .unwrap();
+
+        let package = package_set
+            .get_resolved_packages()
+            .unwrap()
+            .remove("people")
+            .unwrap();
+        let descriptor_names = package
+            .get_descriptors()
+            .into_keys()
+            .collect::<Vec<String>>();
+
+        assert_eq!(
+            descriptor_names,
+            vec![
+                "GENDER",
+                "Person",
+                "Person.Address",
+                "Person.Address.GENDER",
+                "Person.Address.Street"
+            ]
+        );
+
+        if let Some(Descriptor::Message(message_descriptor)) =
+            package.get_descriptors().get("Person")
+        {
+            let field_type = message_descriptor.get_fields()[0].get_field_type();
+            assert_eq!(
+                field_type,
+                FieldType::Object {
+                    package: "people".to_string(),
+                    descriptor: "Person.Address.Street".to_string()
+                }
+            );
+        } else {
+            panic!("Expected message descriptor.");
+        }
+    }
+
+    #[test]
+    fn oneof_descriptors() {
+        let file_descriptor_proto = get_file_descriptor_proto();
+        let mut person = file_descriptor_proto.message_type[0].clone();
+        person.field.append(&mut vec![
+            FieldDescriptorProto {
+                r#type: Some(Type::String.into()),
+                name: Some("email".to_string()),
+                number: Some(3),
+                label: Some(Label::Optional.into()),
+                oneof_index: Some(0),
+                ..Default::default()
+            },
+            FieldDescriptorProto {
+                r#type: Some(Type::String.into()),
+                name: Some("phone".to_string()),
+                number: Some(4),
+                label: Some(Label::Optional.into()),
+                oneof_index: Some(0),
+                ..Default::default()
+            },
+            FieldDescriptorProto {
+                r#type: Some(Type::String.into()),
+                name: Some("nickname".to_string()),
+                number: Some(5),
+                label: Some(Label::Optional.into()),
+                oneof_index: Some(1),
+                proto3_optional: Some(true),
+                ..Default::default()
+            },
+        ]);
+        person.oneof_decl = vec![
+            OneofDescriptorProto {
+                name: Some("contact".to_string()),
+                ..Default::default()
+            },
+            OneofDescriptorProto {
+                name: Some("_nickname".to_string()),
+                ..Default::default()
+            },
+        ];
+
+        let fds_encoded = get_fds_encoded(vec![FileDescriptorProto {
+            message_type: vec![person],
+            ..file_descriptor_proto
+        }]);
+
+        let mut package_set = PackageSet::default();
+        package_set
+            .register_file_descriptor_set_encoded(fds_encoded.as_slice())
+            .unwrap();
+
+        let package = package_set.packages.get("people").unwrap().to_owned();
+        if let Some(Descriptor::Message(message_descriptor)) =
+            package.get_descriptors().get("Person")
+        {
+            let field_names = message_descriptor
+                .get_fields()
+                .into_iter()
+                .map(|field| field.get_name())
+                .collect::<Vec<String>>();
+            assert_eq!(field_names, vec!["id", "otherAttribute", "nickname"]);
+
+            let oneofs = message_descriptor.get_oneofs();
+            assert_eq!(oneofs.len(), 1);
+            assert_eq!(oneofs[0].get_name(), "contact");
+
+            let oneof_field_names = oneofs[0]
+                .get_fields()
+                .into_iter()
+                .map(|field| field.get_name())
+                .collect::<Vec<String>>();
+            assert_eq!(oneof_field_names, vec!["email", "phone"]);
+        } else {
+            panic!("Expected message descriptor.");
+        }
+    }
+
+    #[test]
+    fn map_descriptors() {
+        let file_descriptor_proto = get_file_descriptor_proto();
+        let mut person = file_descriptor_proto.message_type[0].clone();
+        person.field.push(FieldDescriptorProto {
+            r#type: Some(Type::Message.into()),
+            type_name: Some(".people.Person.FriendsEntry".to_string()),
+            name: Some("friends".to_string()),
+            number: Some(3),
+            label: Some(Label::Repeated.into()),
+            ..Default::default()
+        });
+        person.nested_type.push(DescriptorProto {
+            name: Some("FriendsEntry".to_string()),
+            field: vec![
+                FieldDescriptorProto {
+                    r#type: Some(Type::Int64.into()),
+                    name: Some("key".to_string()),
+                    number: Some(1),
+                    label: Some(Label::Optional.into()),
+                    ..Default::default()
+                },
+                FieldDescriptorProto {
+                    r#type: Some(Type::Message.into()),
+                    type_name: Some(".people.Person".to_string()),
+                    name: Some("value".to_string()),
+                    number: Some(2),
+                    label: Some(Label::Optional.into()),
+                    ..Default::default()
+                },
+            ],
+            options: Some(MessageOptions {
+                map_entry: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+
+        let fds_encoded = get_fds_encoded(vec![FileDescriptorProto {
+            message_type: vec![person],
+            ..file_descriptor_proto
+        }]);
+
+        let mut package_set = PackageSet::default();
+        package_set
+            .register_file_descriptor_set_encoded(fds_encoded.as_slice())
+            .unwrap();
+
+        let package = package_set
+            .get_resolved_packages()
+            .unwrap()
+            .remove("people")
+            .unwrap();
+        let field = get_person_field(package.get_descriptors(), "friends").unwrap();
+
+        assert_eq!(field.get_field_modifier(), FieldModifier::Optional);
+        assert_eq!(
+            field.get_field_type(),
+            FieldType::Map {
+                key: ScalarType::I64,
+                value: Box::new(FieldType::Object {
+                    package: "people".to_string(),
+                    descriptor: "Person".to_string()
+                })
+            }
+        );
+    }
+
+    #[test]
+    fn dotted_package_descriptors() {
+        let file_descriptor_proto = get_file_descriptor_proto();
+        let mut person = file_descriptor_proto.message_type[0].clone();
+        person.field = vec![
+            FieldDescriptorProto {
+                r#type: Some(Type::Message.into()),
+                type_name: Some(".acme.people.v1.Person.Address".to_string()),
+                name: Some("address".to_string()),
+                number: Some(1),
+                label: Some(Label::Optional.into()),
+                ..Default::default()
+            },
+            FieldDescriptorProto {
+                r#type: Some(Type::Message.into()),
+                type_name: Some(".acme.billing.v1.Invoice".to_string()),
+                name: Some("invoice".to_string()),
+                number: Some(2),
+                label: Some(Label::Optional.into()),
+                ..Default::default()
+            },
+        ];
+
+        let fds_encoded = get_fds_encoded(vec![
+            FileDescriptorProto {
+                name: Some("invoice.proto".to_string()),
+                package: Some("acme.billing.v1".to_string()),
+                message_type: vec![DescriptorProto {
+                    name: Some("Invoice".to_string()),
+                    ..Default::default()
+                }],
+                enum_type: vec![],
+                ..file_descriptor_proto.clone()
+            },
+            FileDescriptorProto {
+                package: Some("acme.people.v1".to_string()),
+                message_type: vec![DescriptorProto {
+                    nested_type: vec![DescriptorProto {
+                        name: Some("Address".to_string()),
+                        ..Default::default()
+                    }],
+                    ..person
+                }],
+                ..file_descriptor_proto
+            },
+        ]);
+
+        let mut package_set = PackageSet::default();
+        package_set
+            .register_file_descriptor_set_encoded(fds_encoded.as_slice())
+            .unwrap();
+
+        let package = package_set
+            .get_resolved_packages()
+            .unwrap()
+            .remove("acme.people.v1")
+            .unwrap();
+
+        let field = get_person_field(package.get_descriptors(), "address").unwrap();
+        assert_eq!(
+            field.get_field_type(),
+            FieldType::Object {
+                package: "acme.people.v1".to_string(),
+                descriptor: "Person.Address".to_string()
+            }
+        );
+
+        let field = get_person_field(package.get_descriptors(), "invoice").unwrap();
+        assert_eq!(
+            field.get_field_type(),
+            FieldType::Object {
+                package: "acme.billing.v1".to_string(),
+                descriptor: "Invoice".to_string()
+            }
+        );
+    }
+
+    fn get_well_known_package_set(type_name: &str) -> PackageSet {
+        let file_descriptor_proto = get_file_descriptor_proto();
+        let mut person = file_descriptor_proto.message_type[0].clone();
+        person.field.push(FieldDescriptorProto {
+            r#type: Some(Type::Message.into()),
+            type_name: Some(type_name.to_string()),
+            name: Some("reference".to_string()),
+            number: Some(3),
+            label: Some(Label::Optional.into()),
+            ..Default::default()
+        });
+
+        let fds_encoded = get_fds_encoded(vec![FileDescriptorProto {
+            message_type: vec![person],
+            ..file_descriptor_proto
+        }]);
+
+        let mut package_set = PackageSet::default();
+        package_set
+            .register_file_descriptor_set_encoded(fds_encoded.as_slice())
+            .unwrap();
+
+        package_set
+    }
+
+    #[test]
+    fn well_known_descriptors() {
+        let package = get_well_known_package_set(".google.protobuf.Timestamp")
+            .get_resolved_packages()
+            .unwrap()
+            .remove("people")
+            .unwrap();
+        let field = get_person_field(package.get_descriptors(), "reference").unwrap();
+
+        assert_eq!(field.get_field_modifier(), FieldModifier::Optional);
+        assert_eq!(
+            field.get_field_type(),
+            FieldType::WellKnown(WellKnownType::Timestamp)
+        );
+
+        let package = get_well_known_package_set(".google.protobuf.UInt64Value")
+            .get_resolved_packages()
+            .unwrap()
+            .remove("people")
+            .unwrap();
+        let field = get_person_field(package.get_descriptors(), "reference").unwrap();
+
+        assert_eq!(
+            field.get_field_type(),
+            FieldType::WellKnown(WellKnownType::Wrapper(ScalarType::U64))
+        );
+    }
+
+    #[test]
+    fn unsupported_well_known_descriptor() {
+        let mut package_set = get_well_known_package_set(".google.protobuf.Api");
+        let fds_encoded = get_fds_encoded(vec![FileDescriptorProto {
+            name: Some("google/protobuf/api.proto".to_string()),
+            package: Some("google.protobuf".to_string()),
+            message_type: vec![DescriptorProto {
+                name: Some("Api".to_string()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }]);
+        package_set
+            .register_file_descriptor_set_encoded(fds_encoded.as_slice())
+            .unwrap();
+
+        let err = package_set.get_resolved_packages().err().unwrap();
+
+        let expected_location = ErrorLocation {
+            file: "person.proto".to_string(),
+            package: "people".to_string(),
+            path: "people.Person.reference".to_string(),
+            position: None,
+        };
+
+        assert!(matches!(
+            &err,
+            PrutoipaBuildError::UnsupportedType { type_name, .. }
+                if type_name == ".google.protobuf.Api"
+        ));
+        assert_eq!(err.get_location(), Some(&expected_location));
+    }
+
+    #[test]
+    fn enum_field_descriptors() {
+        let file_descriptor_proto = get_file_descriptor_proto();
+        let mut person = file_descriptor_proto.message_type[0].clone();
+        person.field.push(FieldDescriptorProto {
+            r#type: Some(Type::Enum.into()),
+            type_name: Some(".people.GENDER".to_string()),
+            name: Some("gender".to_string()),
+            number: Some(3),
+            label: Some(Label::Optional.into()),
+            ..Default::default()
+        });
+
+        let fds_encoded = get_fds_encoded(vec![FileDescriptorProto {
+            message_type: vec![person],
+            ..file_descriptor_proto
+        }]);
+
+        let mut package_set = PackageSet::default();
+        package_set
+            .register_file_descriptor_set_encoded(fds_encoded.as_slice())
+            .unwrap();
+
+        let package = package_set
+            .get_resolved_packages()
+            .unwrap()
+            .remove("people")
+            .unwrap();
+        let field = get_person_field(package.get_descriptors(), "gender").unwrap();
+
+        assert_eq!(
+            field.get_field_type(),
+            FieldType::Enum {
+                package: "people".to_string(),
+                descriptor: "GENDER".to_string()
+            }
+        );
+    }
+
+    fn get_gender_field(default_value: &str) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            r#type: Some(Type::Enum.into()),
+            type_name: Some(".people.GENDER".to_string()),
+            name: Some("gender".to_string()),
+            number: Some(3),
+            label: Some(Label::Optional.into()),
+            default_value: Some(default_value.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn default_value_descriptors() {
+        let file_descriptor_proto = get_file_descriptor_proto();
+        let mut person = file_descriptor_proto.message_type[0].clone();
+        person.field[0].default_value = Some("42".to_string());
+        person.field.push(get_gender_field("FEMALE"));
+
+        let fds_encoded = get_fds_encoded(vec![FileDescriptorProto {
+            syntax: Some("proto2".to_string()),
+            message_type: vec![person],
+            ..file_descriptor_proto
+        }]);
+
+        let mut package_set = PackageSet::default();
+        package_set
+            .register_file_descriptor_set_encoded(fds_encoded.as_slice())
+            .unwrap();
+
+        let descriptors = package_set
+            .get_resolved_packages()
+            .unwrap()
+            .remove("people")
+            .unwrap()
+            .get_descriptors();
+
+        let id = get_person_field(descriptors.clone(), "id").unwrap();
+        let other_attribute = get_person_field(descriptors.clone(), "otherAttribute").unwrap();
+        let gender = get_person_field(descriptors, "gender").unwrap();
+
+        assert_eq!(id.get_default_value(false), Some(DefaultValue::Int32(42)));
+        assert_eq!(other_attribute.get_default_value(true), None);
+        assert_eq!(
+            gender.get_default_value(false),
+            Some(DefaultValue::Enum {
+                name: "FEMALE".to_string(),
+                number: 1
+            })
+        );
+    }
+
+    #[test]
+    fn implicit_default_value_descriptors() {
+        let fds_encoded = get_fds_encoded(vec![get_file_descriptor_proto()]);
+
+        let mut package_set = PackageSet::default();
+        package_set
+            .register_file_descriptor_set_encoded(fds_encoded.as_slice())
+            .unwrap();
+
+        let descriptors = package_set
+            .get_resolved_packages()
+            .unwrap()
+            .remove("people")
+            .unwrap()
+            .get_descriptors();
+        let id = get_person_field(descriptors, "id").unwrap();
+
+        assert_eq!(id.get_default_value(false), None);
+        assert_eq!(id.get_default_value(true), Some(DefaultValue::Int32(0)));
+    }
+
+    #[test]
+    fn invalid_enum_default_value() {
+        let file_descriptor_proto = get_file_descriptor_proto();
+        let mut person = file_descriptor_proto.message_type[0].clone();
+        person.field.push(get_gender_field("OTHER"));
+
+        let fds_encoded = get_fds_encoded(vec![FileDescriptorProto {
+            syntax: Some("proto2".to_string()),
+            message_type: vec![person],
+            ..file_descriptor_proto
+        }]);
+
+        let mut package_set = PackageSet::default();
+        package_set
+            .register_file_descriptor_set_encoded(fds_encoded.as_slice())
+            .unwrap();
+
+        let err = package_set.get_resolved_packages().err().unwrap();
+
+        let expected_location = ErrorLocation {
+            file: "person.proto".to_string(),
+            package: "people".to_string(),
+            path: "people.Person.gender".to_string(),
+            position: None,
+        };
+
+        assert!(matches!(
+            &err,
+            PrutoipaBuildError::InvalidDefaultValue { default_value, reason, .. }
+                if default_value == "OTHER"
+                    && reason == "not a value of enum 'people.GENDER'"
+        ));
+        assert_eq!(err.get_location(), Some(&expected_location));
+    }
+
+    #[test]
+    fn descriptor_comments() {
+        let get_location = |path: Vec<i32>, comment: &str| Location {
+            path,
+            leading_comments: Some(comment.to_string()),
+            ..Default::default()
+        };
+
+        let fds_encoded = get_fds_encoded(vec![FileDescriptorProto {
+            source_code_info: Some(SourceCodeInfo {
+                location: vec![
+                    get_location(vec![4, 0], " A person.\n"),
+                    get_location(vec![4, 0, 2, 1], " Some attribute.\n"),
+                    get_location(vec![5, 0], " A gender.\n"),
+                    get_location(vec![5, 0, 2, 1], " Female.\n"),
+                ],
+            }),
+            ..get_file_descriptor_proto()
+        }]);
+
+        let mut package_set = PackageSet::default();
+        package_set
+            .register_file_descriptor_set_encoded(fds_encoded.as_slice())
+            .unwrap();
+
+        let descriptors = package_set
+            .get_resolved_packages()
+            .unwrap()
+            .remove("people")
+            .unwrap()
+            .get_descriptors();
+
+        if let Some(Descriptor::Message(message_descriptor)) = descriptors.get("Person") {
+            let fields = message_descriptor.get_fields();
+
+            assert_eq!(
+                message_descriptor.get_comments().get_description(false),
+                Some("A person.".to_string())
+            );
+            assert_eq!(fields[0].get_comments().get_description(false), None);
+            assert_eq!(
+                fields[1].get_comments().get_description(false),
+                Some("Some attribute.".to_string())
+            );
+        } else {
+            panic!("Expected message descriptor.");
+        }
+
+        if let Some(Descriptor::Enum(enum_descriptor)) = descriptors.get("GENDER") {
+            let values = enum_descriptor.get_values();
+
+            assert_eq!(
+                enum_descriptor.get_comments().get_description(false),
+                Some("A gender.".to_string())
+            );
+            assert_eq!(values[0].comments.get_description(false), None);
+            assert_eq!(
+                values[1].comments.get_description(false),
+                Some("Female.".to_string())
+            );
+        } else {
+            panic!("Expected enum descriptor.");
+        }
+    }
+
+    #[test]
+    fn deprecated_descriptors() {
+        let mut file_descriptor_proto = get_file_descriptor_proto();
+        let person = &mut file_descriptor_proto.message_type[0];
+        person.options = Some(MessageOptions {
+            deprecated: Some(true),
+            ..Default::default()
+        });
+        person.field[1].options = Some(FieldOptions {
+            deprecated: Some(true),
+            ..Default::default()
+        });
+        let gender = &mut file_descriptor_proto.enum_type[0];
+        gender.options = Some(EnumOptions {
+            deprecated: Some(true),
+            ..Default::default()
+        });
+        gender.value[1].options = Some(EnumValueOptions {
+            deprecated: Some(true),
+            ..Default::default()
+        });
+
+        let fds_encoded = get_fds_encoded(vec![file_descriptor_proto]);
+
+        let mut package_set = PackageSet::default();
+        package_set
+            .register_file_descriptor_set_encoded(fds_encoded.as_slice())
+            .unwrap();
+
+        let descriptors = package_set
+            .get_resolved_packages()
+            .unwrap()
+            .remove("people")
+            .unwrap()
+            .get_descriptors();
+
+        if let Some(Descriptor::Message(message_descriptor)) = descriptors.get("Person") {
+            let fields = message_descriptor.get_fields();
+
+            assert!(message_descriptor.is_deprecated());
+            assert!(!fields[0].is_deprecated());
+            assert!(fields[1].is_deprecated());
+        } else {
+            panic!("Expected message descriptor.");
+        }
+
+        if let Some(Descriptor::Enum(enum_descriptor)) = descriptors.get("GENDER") {
+            let values = enum_descriptor.get_values();
+
+            assert!(enum_descriptor.is_deprecated());
+            assert!(!values[0].deprecated);
+            assert!(values[1].deprecated);
+        } else {
+            panic!("Expected enum descriptor.");
+        }
+    }
+
+    #[test]
+    fn package_defined_at_several_files() {
+        let file_descriptor_proto = get_file_descriptor_proto();
+        let fds_encoded = get_fds_encoded(vec![
+            file_descriptor_proto.clone(),
+            FileDescriptorProto {
+                name: Some("address.proto".to_string()),
+                syntax: Some("proto2".to_string()),
+                message_type: vec![DescriptorProto {
+                    name: Some("Address".to_string()),
+                    ..file_descriptor_proto.message_type[0].clone()
+                }],
+                enum_type: vec![],
+                ..file_descriptor_proto.clone()
+            },
+            // Registering the same file again is a no-op
+            file_descriptor_proto,
+        ]);
+
+        let mut package_set = PackageSet::default();
+        package_set
+            .register_file_descriptor_set_encoded(fds_encoded.as_slice())
+            .unwrap();
+
+        assert_eq!(package_set.packages.len(), 1);
+
+        let package = package_set.packages.get("people").unwrap().to_owned();
+        let package_files = package
+            .get_files()
+            .into_iter()
+            .map(|file| (file.get_name(), file.get_syntax()))
+            .collect::<Vec<(String, Syntax)>>();
+
+        assert_eq!(
+            package_files,
+            vec![
+                ("person.proto".to_string(), Syntax::Proto3),
+                ("address.proto".to_string(), Syntax::Proto2)
+            ]
+        );
+
+        let descriptor_names = package
+            .get_descriptors()
+            .into_keys()
+            .collect::<Vec<String>>();
+        assert_eq!(descriptor_names, vec!["Address", "GENDER", "Person"]);
+    }
+
+    #[test]
+    fn descriptor_defined_at_several_files() {
+        let file_descriptor_proto = get_file_descriptor_proto();
         let fds_encoded = get_fds_encoded(vec![
-            get_file_descriptor_proto(),
-            get_file_descriptor_proto(),
+            file_descriptor_proto.clone(),
+            FileDescriptorProto {
+                name: Some("person_v2.proto".to_string()),
+                enum_type: vec![],
+                ..file_descriptor_proto
+            },
         ]);
 
         let mut package_set = PackageSet::default();
         let err = package_set
             .register_file_descriptor_set_encoded(fds_encoded.as_slice())
-            .err();
+            .err()
+            .unwrap();
+
+        let expected_location = ErrorLocation {
+            file: "person_v2.proto".to_string(),
+            package: "people".to_string(),
+            path: "people.Person".to_string(),
+            position: None,
+        };
+
+        assert!(matches!(
+            &err,
+            PrutoipaBuildError::DuplicateDescriptor { previous_file, .. }
+                if previous_file == "person.proto"
+        ));
+        assert_eq!(err.get_location(), Some(&expected_location));
+    }
+
+    #[test]
+    fn failed_file_registration() {
+        let file_descriptor_proto = get_file_descriptor_proto();
+        let mut package_set = PackageSet::default();
+        package_set
+            .register_file_descriptor_set_encoded(
+                get_fds_encoded(vec![file_descriptor_proto.clone()]).as_slice(),
+            )
+            .unwrap();
+
+        let err = package_set
+            .register_file_descriptor_set_encoded(
+                get_fds_encoded(vec![FileDescriptorProto {
+                    name: Some("person_v2.proto".to_string()),
+                    enum_type: vec![],
+                    extension: vec![FieldDescriptorProto {
+                        r#type: Some(Type::String.into()),
+                        name: Some("nickname".to_string()),
+                        number: Some(100),
+                        label: Some(Label::Optional.into()),
+                        extendee: Some(".people.Person".to_string()),
+                        ..Default::default()
+                    }],
+                    ..file_descriptor_proto
+                }])
+                .as_slice(),
+            )
+            .err()
+            .unwrap();
 
-        let expected_err = Some(PrutoipaBuildError::InvalidData(
-            "Package 'people' already defined.".to_string(),
+        assert!(matches!(
+            err,
+            PrutoipaBuildError::DuplicateDescriptor { .. }
         ));
 
-        assert_eq!(format!("{err:?}"), format!("{expected_err:?}"));
+        // Neither the package nor the warnings keep anything of the failed file
+        let package = package_set.packages.get("people").unwrap();
+        let package_files = package
+            .get_files()
+            .into_iter()
+            .map(|file| file.get_name())
+            .collect::<Vec<String>>();
+        let descriptor_names = package
+            .get_descriptors()
+            .into_keys()
+            .collect::<Vec<String>>();
+
+        assert_eq!(package_files, vec!["person.proto"]);
+        assert_eq!(descriptor_names, vec!["GENDER", "Person"]);
+        assert!(package_set.get_warnings().is_empty());
     }
 
     #[test]
@@ -203,13 +1358,24 @@ mod tests {
         let mut package_set = PackageSet::default();
         let err = package_set
             .register_file_descriptor_set_encoded(fds_encoded.as_slice())
-            .err();
+            .err()
+            .unwrap();
 
-        let expected_err = Some(PrutoipaBuildError::InvalidData(
-            "Expected message name.".to_string(),
-        ));
+        let expected_location = ErrorLocation {
+            file: "person.proto".to_string(),
+            package: "people".to_string(),
+            path: "people".to_string(),
+            position: None,
+        };
 
-        assert_eq!(format!("{err:?}"), format!("{expected_err:?}"));
+        assert!(matches!(
+            &err,
+            PrutoipaBuildError::MissingName {
+                kind: "message",
+                ..
+            }
+        ));
+        assert_eq!(err.get_location(), Some(&expected_location));
     }
 
     #[test]
@@ -226,12 +1392,251 @@ mod tests {
         let mut package_set = PackageSet::default();
         let err = package_set
             .register_file_descriptor_set_encoded(fds_encoded.as_slice())
-            .err();
+            .err()
+            .unwrap();
+
+        let expected_location = ErrorLocation {
+            file: "person.proto".to_string(),
+            package: "people".to_string(),
+            path: "people".to_string(),
+            position: None,
+        };
+
+        assert!(matches!(
+            &err,
+            PrutoipaBuildError::MissingName { kind: "enum", .. }
+        ));
+        assert_eq!(err.get_location(), Some(&expected_location));
+    }
+
+    #[test]
+    fn enum_value_without_name() {
+        let file_descriptor_proto = get_file_descriptor_proto();
+        let mut gender = file_descriptor_proto.enum_type[0].clone();
+        gender.value[1].name = None;
+
+        let fds_encoded = get_fds_encoded(vec![FileDescriptorProto {
+            enum_type: vec![gender],
+            source_code_info: Some(SourceCodeInfo {
+                location: vec![Location {
+                    path: vec![5, 0, 2, 1],
+                    span: vec![5, 2, 13],
+                    ..Default::default()
+                }],
+            }),
+            ..file_descriptor_proto
+        }]);
+
+        let mut package_set = PackageSet::default();
+        let err = package_set
+            .register_file_descriptor_set_encoded(fds_encoded.as_slice())
+            .err()
+            .unwrap();
+
+        let expected_location = ErrorLocation {
+            file: "person.proto".to_string(),
+            package: "people".to_string(),
+            path: "people.GENDER".to_string(),
+            position: Some((6, 3)),
+        };
+
+        assert!(matches!(
+            &err,
+            PrutoipaBuildError::MissingName {
+                kind: "enum value",
+                ..
+            }
+        ));
+        assert_eq!(err.get_location(), Some(&expected_location));
+    }
+
+    #[test]
+    fn message_field_without_type_name() {
+        let file_descriptor_proto = get_file_descriptor_proto();
+        let mut person = file_descriptor_proto.message_type[0].clone();
+        person.field.push(FieldDescriptorProto {
+            r#type: Some(Type::Message.into()),
+            name: Some("address".to_string()),
+            number: Some(3),
+            label: Some(Label::Optional.into()),
+            ..Default::default()
+        });
+
+        let fds_encoded = get_fds_encoded(vec![FileDescriptorProto {
+            message_type: vec![person],
+            source_code_info: Some(SourceCodeInfo {
+                location: vec![Location {
+                    path: vec![4, 0, 2, 2],
+                    span: vec![7, 4, 28],
+                    ..Default::default()
+                }],
+            }),
+            ..file_descriptor_proto
+        }]);
+
+        let mut package_set = PackageSet::default();
+        let err = package_set
+            .register_file_descriptor_set_encoded(fds_encoded.as_slice())
+            .err()
+            .unwrap();
+
+        let expected_location = ErrorLocation {
+            file: "person.proto".to_string(),
+            package: "people".to_string(),
+            path: "people.Person.address".to_string(),
+            position: Some((8, 5)),
+        };
+
+        assert!(matches!(
+            &err,
+            PrutoipaBuildError::InvalidField { reason, .. }
+                if reason == "expected type name for type Message"
+        ));
+        assert_eq!(err.get_location(), Some(&expected_location));
+        assert_eq!(
+            err.to_string(),
+            "Invalid field 'people.Person.address' (person.proto:8:5): expected type name for type Message."
+        );
+    }
+
+    #[test]
+    fn repeated_oneof_field() {
+        let file_descriptor_proto = get_file_descriptor_proto();
+        let mut person = file_descriptor_proto.message_type[0].clone();
+        person.oneof_decl.push(OneofDescriptorProto {
+            name: Some("contact".to_string()),
+            ..Default::default()
+        });
+        person.field.push(FieldDescriptorProto {
+            r#type: Some(Type::String.into()),
+            name: Some("email".to_string()),
+            number: Some(3),
+            label: Some(Label::Repeated.into()),
+            oneof_index: Some(0),
+            ..Default::default()
+        });
+
+        let fds_encoded = get_fds_encoded(vec![FileDescriptorProto {
+            message_type: vec![person],
+            ..file_descriptor_proto
+        }]);
+
+        let mut package_set = PackageSet::default();
+        let err = package_set
+            .register_file_descriptor_set_encoded(fds_encoded.as_slice())
+            .err()
+            .unwrap();
+
+        let expected_location = ErrorLocation {
+            file: "person.proto".to_string(),
+            package: "people".to_string(),
+            path: "people.Person.email".to_string(),
+            position: None,
+        };
 
-        let expected_err = Some(PrutoipaBuildError::InvalidData(
-            "Expected enum name.".to_string(),
+        assert!(matches!(
+            &err,
+            PrutoipaBuildError::InvalidField { reason, .. }
+                if reason == "expected one of member to be optional, found Repeated"
         ));
+        assert_eq!(err.get_location(), Some(&expected_location));
+    }
+
+    #[test]
+    fn undocumented_descriptors_warnings() {
+        let file_descriptor_proto = get_file_descriptor_proto();
+        let extension = FieldDescriptorProto {
+            r#type: Some(Type::String.into()),
+            name: Some("nickname".to_string()),
+            number: Some(100),
+            label: Some(Label::Optional.into()),
+            extendee: Some(".people.Person".to_string()),
+            ..Default::default()
+        };
+        let mut person = file_descriptor_proto.message_type[0].clone();
+        person.extension.push(FieldDescriptorProto {
+            name: Some("alias".to_string()),
+            ..extension.clone()
+        });
+
+        let fds_encoded = get_fds_encoded(vec![FileDescriptorProto {
+            message_type: vec![person],
+            service: vec![ServiceDescriptorProto {
+                name: Some("Directory".to_string()),
+                method: vec![MethodDescriptorProto {
+                    name: Some("List".to_string()),
+                    input_type: Some(".people.Person".to_string()),
+                    output_type: Some(".people.Person".to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            extension: vec![
+                extension,
+                // Custom options are not reported
+                FieldDescriptorProto {
+                    name: Some("route".to_string()),
+                    extendee: Some(".google.protobuf.MethodOptions".to_string()),
+                    ..Default::default()
+                },
+            ],
+            source_code_info: Some(SourceCodeInfo {
+                location: vec![Location {
+                    path: vec![6, 0, 2, 0],
+                    span: vec![12, 2, 40],
+                    ..Default::default()
+                }],
+            }),
+            ..file_descriptor_proto
+        }]);
+
+        let mut package_set = PackageSet::default();
+        package_set
+            .register_file_descriptor_set_encoded(fds_encoded.as_slice())
+            .unwrap();
+
+        let location = |path: &str, position| ErrorLocation {
+            file: "person.proto".to_string(),
+            package: "people".to_string(),
+            path: path.to_string(),
+            position,
+        };
+        let expected_warnings = vec![
+            Warning::new(
+                location("people.nickname", None),
+                "Extensions are not documented, 'nickname' extends 'people.Person'".to_string(),
+            ),
+            Warning::new(
+                location("people.Person.alias", None),
+                "Extensions are not documented, 'alias' extends 'people.Person'".to_string(),
+            ),
+        ];
+        let expected_unannotated_method_warnings = vec![Warning::new(
+            location("people.Directory.List", Some((13, 3))),
+            "Methods without a google.api.http annotation are not documented".to_string(),
+        )];
+
+        assert_eq!(package_set.get_warnings(), expected_warnings);
+        assert_eq!(
+            package_set.get_unannotated_method_warnings(),
+            expected_unannotated_method_warnings
+        );
+
+        // Registering the same file again does not repeat the warnings
+        package_set
+            .register_file_descriptor_set_encoded(fds_encoded.as_slice())
+            .unwrap();
+
+        assert_eq!(package_set.get_warnings().len(), 2);
+        assert_eq!(package_set.get_unannotated_method_warnings().len(), 1);
+
+        // Methods are not reported when their annotations were dropped while decoding
+        let mut package_set = PackageSet::default();
+        package_set
+            .register_file_descriptor_set(prost::Message::decode(fds_encoded.as_slice()).unwrap())
+            .unwrap();
 
-        assert_eq!(format!("{err:?}"), format!("{expected_err:?}"));
+        assert_eq!(package_set.get_warnings(), expected_warnings);
+        assert!(package_set.get_unannotated_method_warnings().is_empty());
     }
 }
@@ -0,0 +1,252 @@
+//! `protoc` plugin, generating from the `CodeGeneratorRequest` that `protoc` or `buf` write to
+//! `protoc-gen-prutoipa`.
+
+use std::collections::BTreeSet;
+
+use prost_types::compiler::{
+    code_generator_response::{Feature, File},
+    CodeGeneratorResponse,
+};
+
+use crate::{
+    Builder, EnumRepresentation, JsonMapping, OneOfStyle, PresencePolicy, PropertyNaming,
+    PropertyPresence, PrutoipaBuildError, Warning,
+};
+#[cfg(feature = "document")]
+use crate::{DocumentFormat, OpenApiVersion};
+
+/// Output of the plugin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum PluginOutput {
+    /// `<package>.utoipa.rs` files with the utoipa implementations
+    Rust,
+    /// `<package>.openapi.json` or `<package>.openapi.yaml` files with the OpenAPI documents
+    #[cfg(feature = "document")]
+    Document(DocumentFormat),
+}
+
+impl PluginOutput {
+    fn get_file_name(&self, package_name: &str) -> String {
+        match self {
+            Self::Rust => format!("{}.utoipa.rs", package_name),
+            #[cfg(feature = "document")]
+            Self::Document(format) => {
+                format!("{}.openapi.{}", package_name, format.get_extension())
+            }
+        }
+    }
+}
+
+/// Returns the response to an encoded `CodeGeneratorRequest`, with the warnings about the
+/// descriptors. As plugins must, failures are reported at the `error` of the response.
+///
+/// Only the packages of the files to generate are generated. The parameter is a comma-separated
+/// list of options mapped to `Builder` settings:
+///
+/// - `format=rust|json|yaml`, can be repeated, defaults to `rust`
+/// - `strict`, `warn_unannotated_methods`, `enum_values`, `implicit_defaults` and
+///   `markdown_comments`
+/// - `enum_representation=string|integer|either`
+/// - `oneof_style=flattened|nested`
+/// - `json_mapping=canonical|lenient|numeric`
+/// - `property_naming=proto_name|json_name|snake_case|camel_case`
+/// - `presence_policy=strict|wire_accurate`
+/// - `field_presence=<package.Message.field>=required|optional|nullable`, can be repeated
+/// - `openapi=<title>:<version>`
+/// - `openapi_version=3.0|3.1`
+///
+/// The `json` and `yaml` formats and `openapi_version` require the `document` feature.
+pub fn generate_plugin_response(request_encoded: &[u8]) -> (CodeGeneratorResponse, Vec<Warning>) {
+    let response = CodeGeneratorResponse {
+        supported_features: Some(Feature::Proto3Optional as u64),
+        ..Default::default()
+    };
+
+    match generate_plugin_files(request_encoded) {
+        Ok((file, warnings)) => (CodeGeneratorResponse { file, ..response }, warnings),
+        Err(err) => (
+            CodeGeneratorResponse {
+                error: Some(err.to_string()),
+                ..response
+            },
+            Vec::new(),
+        ),
+    }
+}
+
+fn generate_plugin_files(
+    request_encoded: &[u8],
+) -> Result<(Vec<File>, Vec<Warning>), PrutoipaBuildError> {
+    let mut builder = Builder::new();
+    let request = builder
+        .package_set
+        .register_code_generator_request_encoded(request_encoded)?;
+    let outputs = apply_parameter(&mut builder, request.parameter())?;
+
+    // Imported files are registered to resolve the references to them, but not generated
+    builder.generated_packages = Some(
+        request
+            .proto_file
+            .iter()
+            .filter(|file| {
+                request
+                    .file_to_generate
+                    .iter()
+                    .any(|file_name| file_name == file.name())
+            })
+            .map(|file| file.package().to_string())
+            .collect::<BTreeSet<String>>(),
+    );
+
+    let mut files = Vec::new();
+    let mut warnings = Vec::new();
+    for output in outputs {
+        let write_factory = |_| Ok(Vec::<u8>::new());
+        let generated = match output {
+            PluginOutput::Rust => builder.generate(write_factory)?,
+            #[cfg(feature = "document")]
+            PluginOutput::Document(format) => builder.generate_documents(format, write_factory)?,
+        };
+
+        // Every output is generated from the same descriptors, so they share the warnings
+        warnings = generated.warnings;
+        files.extend(
+            generated
+                .files
+                .into_iter()
+                .map(|(package_name, content)| File {
+                    name: Some(output.get_file_name(&package_name)),
+                    content: Some(String::from_utf8_lossy(&content).into_owned()),
+                    ..Default::default()
+                }),
+        );
+    }
+
+    Ok((files, warnings))
+}
+
+/// Applies the options of the parameter to the `Builder`, returning the outputs to generate.
+fn apply_parameter(
+    builder: &mut Builder,
+    parameter: &str,
+) -> Result<BTreeSet<PluginOutput>, PrutoipaBuildError> {
+    let mut outputs = BTreeSet::new();
+
+    for option in parameter.split(',').filter(|option| !option.is_empty()) {
+        let invalid_parameter = |reason: &str| PrutoipaBuildError::InvalidParameter {
+            parameter: option.to_string(),
+            reason: reason.to_string(),
+        };
+
+        match option.split_once('=').unwrap_or((option, "")) {
+            ("format", "rust") => {
+                outputs.insert(PluginOutput::Rust);
+            }
+            #[cfg(feature = "document")]
+            ("format", "json") => {
+                outputs.insert(PluginOutput::Document(DocumentFormat::Json));
+            }
+            #[cfg(feature = "document")]
+            ("format", "yaml") => {
+                outputs.insert(PluginOutput::Document(DocumentFormat::Yaml));
+            }
+            #[cfg(not(feature = "document"))]
+            ("format", "json" | "yaml") | ("openapi_version", _) => {
+                return Err(invalid_parameter("requires the document feature"))
+            }
+            ("format", _) => return Err(invalid_parameter("expected rust, json or yaml")),
+            ("strict", "") => {
+                builder.strict();
+            }
+            ("warn_unannotated_methods", "") => {
+                builder.warn_unannotated_methods();
+            }
+            ("enum_values", "") => {
+                builder.generate_enum_values();
+            }
+            ("implicit_defaults", "") => {
+                builder.document_implicit_defaults();
+            }
+            ("markdown_comments", "") => {
+                builder.markdown_comments();
+            }
+            ("enum_representation", value) => {
+                builder.enum_representation(match value {
+                    "string" => EnumRepresentation::String,
+                    "integer" => EnumRepresentation::Integer,
+                    "either" => EnumRepresentation::Either,
+                    _ => return Err(invalid_parameter("expected string, integer or either")),
+                });
+            }
+            ("oneof_style", value) => {
+                builder.oneof_style(match value {
+                    "flattened" => OneOfStyle::Flattened,
+                    "nested" => OneOfStyle::Nested,
+                    _ => return Err(invalid_parameter("expected flattened or nested")),
+                });
+            }
+            ("json_mapping", value) => {
+                builder.json_mapping(match value {
+                    "canonical" => JsonMapping::Canonical,
+                    "lenient" => JsonMapping::Lenient,
+                    "numeric" => JsonMapping::Numeric,
+                    _ => return Err(invalid_parameter("expected canonical, lenient or numeric")),
+                });
+            }
+            ("property_naming", value) => {
+                builder.property_naming(match value {
+                    "proto_name" => PropertyNaming::ProtoName,
+                    "json_name" => PropertyNaming::JsonName,
+                    "snake_case" => PropertyNaming::SnakeCase,
+                    "camel_case" => PropertyNaming::CamelCase,
+                    _ => {
+                        return Err(invalid_parameter(
+                            "expected proto_name, json_name, snake_case or camel_case",
+                        ))
+                    }
+                });
+            }
+            ("presence_policy", value) => {
+                builder.presence_policy(match value {
+                    "strict" => PresencePolicy::Strict,
+                    "wire_accurate" => PresencePolicy::WireAccurate,
+                    _ => return Err(invalid_parameter("expected strict or wire_accurate")),
+                });
+            }
+            ("field_presence", value) => {
+                let presence = match value.split_once('=') {
+                    Some((field, "required")) => (field, PropertyPresence::Required),
+                    Some((field, "optional")) => (field, PropertyPresence::Optional),
+                    Some((field, "nullable")) => (field, PropertyPresence::Nullable),
+                    _ => {
+                        return Err(invalid_parameter(
+                            "expected <field>=required, <field>=optional or <field>=nullable",
+                        ))
+                    }
+                };
+                builder.field_presence(presence.0, presence.1);
+            }
+            ("openapi", value) => match value.rsplit_once(':') {
+                Some((title, version)) if !title.is_empty() && !version.is_empty() => {
+                    builder.openapi(title, version);
+                }
+                _ => return Err(invalid_parameter("expected <title>:<version>")),
+            },
+            #[cfg(feature = "document")]
+            ("openapi_version", value) => {
+                builder.openapi_version(match value {
+                    "3.0" => OpenApiVersion::V3_0,
+                    "3.1" => OpenApiVersion::V3_1,
+                    _ => return Err(invalid_parameter("expected 3.0 or 3.1")),
+                });
+            }
+            _ => return Err(invalid_parameter("unknown option")),
+        }
+    }
+
+    if outputs.is_empty() {
+        outputs.insert(PluginOutput::Rust);
+    }
+
+    Ok(outputs)
+}
@@ -0,0 +1,321 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    descriptor::{
+        enum_descriptor::EnumValue,
+        message_descriptor::{
+            default_value::DefaultValue,
+            well_known::{WellKnownType, WELL_KNOWN_PACKAGE},
+        },
+        Descriptor,
+    },
+    error::{ErrorLocation, PrutoipaBuildError},
+    package::Package,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DescriptorKind {
+    Message,
+    Enum,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    pub package: String,
+    pub descriptor: String,
+    pub kind: DescriptorKind,
+}
+
+/// Symbol table of every descriptor registered at a `PackageSet`, keyed by its fully-qualified
+/// name.
+#[derive(Debug, Clone, Default)]
+pub struct Resolver {
+    namespaces: BTreeSet<String>,
+    symbols: BTreeMap<String, Symbol>,
+    enum_values: BTreeMap<String, Vec<EnumValue>>,
+}
+
+impl Resolver {
+    pub fn new(packages: &BTreeMap<String, Package>) -> Self {
+        let mut resolver = Self::default();
+
+        resolver.register_namespace(WELL_KNOWN_PACKAGE);
+        WellKnownType::get_descriptors()
+            .into_iter()
+            .for_each(|(descriptor_name, kind)| {
+                resolver.symbols.insert(
+                    get_full_name(WELL_KNOWN_PACKAGE, descriptor_name),
+                    Symbol {
+                        package: WELL_KNOWN_PACKAGE.to_string(),
+                        descriptor: descriptor_name.to_string(),
+                        kind,
+                    },
+                );
+            });
+
+        packages.iter().for_each(|(package_name, package)| {
+            resolver.register_namespace(package_name);
+
+            package
+                .get_descriptors()
+                .into_iter()
+                .for_each(|(descriptor_name, descriptor)| {
+                    let full_name = get_full_name(package_name, &descriptor_name);
+                    let kind = match descriptor {
+                        Descriptor::Message(_) => DescriptorKind::Message,
+                        Descriptor::Enum(enum_descriptor) => {
+                            resolver
+                                .enum_values
+                                .insert(full_name.clone(), enum_descriptor.get_values());
+                            DescriptorKind::Enum
+                        }
+                    };
+
+                    resolver.symbols.insert(
+                        full_name,
+                        Symbol {
+                            package: package_name.clone(),
+                            descriptor: descriptor_name,
+                            kind,
+                        },
+                    );
+                });
+        });
+
+        resolver
+    }
+
+    fn register_namespace(&mut self, package_name: &str) {
+        let mut namespace = String::new();
+        package_name.split('.').for_each(|segment| {
+            namespace = get_full_name(&namespace, segment);
+            self.namespaces.insert(namespace.clone());
+        });
+    }
+
+    /// Resolves `type_name` as referenced from `scope`, the fully-qualified name of the message
+    /// containing the reference.
+    ///
+    /// Fully-qualified names start with a dot. Otherwise, protobuf scoping rules apply: the first
+    /// segment is searched from the innermost scope outwards and the rest of the name must be
+    /// found at the scope where the first segment was found.
+    pub fn resolve(&self, type_name: &str, scope: &str) -> Result<Symbol, PrutoipaBuildError> {
+        let full_name = match type_name.strip_prefix('.') {
+            Some(full_name) => Some(full_name.to_string()),
+            None => {
+                let first_segment = type_name.split('.').next().unwrap_or_default();
+                let mut scopes = get_scopes(scope);
+                scopes.push(String::new());
+
+                scopes
+                    .into_iter()
+                    .find(|scope| {
+                        let candidate = get_full_name(scope, first_segment);
+                        self.symbols.contains_key(&candidate)
+                            || self.namespaces.contains(&candidate)
+                    })
+                    .map(|scope| get_full_name(&scope, type_name))
+            }
+        };
+
+        full_name
+            .and_then(|full_name| self.symbols.get(&full_name))
+            .cloned()
+            .ok_or_else(|| PrutoipaBuildError::UnknownType {
+                location: ErrorLocation::default().into(),
+                type_name: type_name.to_string(),
+            })
+    }
+}
+
+impl Resolver {
+    /// Completes an enum default value known by name or number with the value of the enum.
+    pub fn resolve_enum_value(
+        &self,
+        package: &str,
+        descriptor: &str,
+        default_value: &DefaultValue,
+    ) -> Result<DefaultValue, PrutoipaBuildError> {
+        let full_name = get_full_name(package, descriptor);
+
+        self.enum_values
+            .get(&full_name)
+            .and_then(|enum_values| {
+                enum_values
+                    .iter()
+                    .find(|enum_value| match default_value {
+                        DefaultValue::EnumName(name) => enum_value.name == *name,
+                        DefaultValue::EnumNumber(number) => enum_value.number == *number,
+                        _ => false,
+                    })
+                    // Proto3 enums must start with the zero value, proto2 ones default to the first
+                    .or(match default_value {
+                        DefaultValue::EnumNumber(_) => enum_values.first(),
+                        _ => None,
+                    })
+            })
+            .map(|enum_value| DefaultValue::Enum {
+                name: enum_value.name.clone(),
+                number: enum_value.number,
+            })
+            .ok_or_else(|| PrutoipaBuildError::InvalidDefaultValue {
+                location: ErrorLocation::default().into(),
+                default_value: match default_value {
+                    DefaultValue::EnumNumber(number) => number.to_string(),
+                    DefaultValue::EnumName(name) => name.clone(),
+                    _ => format!("{default_value:?}"),
+                },
+                reason: format!("not a value of enum '{full_name}'"),
+            })
+    }
+}
+
+fn get_full_name(scope: &str, name: &str) -> String {
+    match scope.is_empty() {
+        true => name.to_string(),
+        false => format!("{scope}.{name}"),
+    }
+}
+
+/// Returns `scope` and all its parents, innermost first.
+fn get_scopes(scope: &str) -> Vec<String> {
+    let segments = scope.split('.').collect::<Vec<&str>>();
+
+    (1..=segments.len())
+        .rev()
+        .map(|len| segments[..len].join("."))
+        .filter(|scope| !scope.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost_types::{
+        field_descriptor_proto::{Label, Type},
+        DescriptorProto, FieldDescriptorProto, FileDescriptorProto,
+    };
+
+    use crate::{
+        package_set::PackageSet,
+        tests::{get_fds_encoded, get_file_descriptor_proto},
+    };
+
+    fn get_package_set(type_name: &str, r#type: Type) -> PackageSet {
+        let file_descriptor_proto = get_file_descriptor_proto();
+        let mut person = file_descriptor_proto.message_type[0].clone();
+        person.field.push(FieldDescriptorProto {
+            r#type: Some(r#type.into()),
+            type_name: Some(type_name.to_string()),
+            name: Some("reference".to_string()),
+            number: Some(3),
+            label: Some(Label::Optional.into()),
+            ..Default::default()
+        });
+        person.nested_type.push(DescriptorProto {
+            name: Some("Address".to_string()),
+            ..Default::default()
+        });
+
+        let fds_encoded = get_fds_encoded(vec![
+            FileDescriptorProto {
+                name: Some("common.proto".to_string()),
+                package: Some("acme.common".to_string()),
+                message_type: vec![DescriptorProto {
+                    name: Some("Money".to_string()),
+                    ..Default::default()
+                }],
+                enum_type: vec![],
+                ..file_descriptor_proto.clone()
+            },
+            FileDescriptorProto {
+                package: Some("acme.people".to_string()),
+                message_type: vec![person],
+                ..file_descriptor_proto
+            },
+        ]);
+
+        let mut package_set = PackageSet::default();
+        package_set
+            .register_file_descriptor_set_encoded(fds_encoded.as_slice())
+            .unwrap();
+
+        package_set
+    }
+
+    #[test]
+    fn resolve_symbols() {
+        let package_set = get_package_set(".acme.common.Money", Type::Message);
+        let resolver = Resolver::new(&package_set.get_resolved_packages().unwrap());
+        let scope = "acme.people.Person";
+
+        let money = Symbol {
+            package: "acme.common".to_string(),
+            descriptor: "Money".to_string(),
+            kind: DescriptorKind::Message,
+        };
+        let address = Symbol {
+            package: "acme.people".to_string(),
+            descriptor: "Person.Address".to_string(),
+            kind: DescriptorKind::Message,
+        };
+        let gender = Symbol {
+            package: "acme.people".to_string(),
+            descriptor: "GENDER".to_string(),
+            kind: DescriptorKind::Enum,
+        };
+
+        assert_eq!(
+            resolver.resolve(".acme.common.Money", scope).unwrap(),
+            money
+        );
+        assert_eq!(resolver.resolve("common.Money", scope).unwrap(), money);
+        assert_eq!(resolver.resolve("acme.common.Money", scope).unwrap(), money);
+        assert_eq!(resolver.resolve("Address", scope).unwrap(), address);
+        assert_eq!(resolver.resolve("Person.Address", scope).unwrap(), address);
+        assert_eq!(resolver.resolve("GENDER", scope).unwrap(), gender);
+
+        // `common` is found at `acme.common`, so `acme.common.Address` must exist.
+        assert!(resolver.resolve("common.Address", scope).is_err());
+    }
+
+    #[test]
+    fn dangling_reference() {
+        let package_set = get_package_set(".acme.common.Wallet", Type::Message);
+        let err = package_set.get_resolved_packages().err().unwrap();
+
+        let expected_location = ErrorLocation {
+            file: "person.proto".to_string(),
+            package: "acme.people".to_string(),
+            path: "acme.people.Person.reference".to_string(),
+            position: None,
+        };
+
+        assert!(matches!(
+            &err,
+            PrutoipaBuildError::UnknownType { type_name, .. }
+                if type_name == ".acme.common.Wallet"
+        ));
+        assert_eq!(err.get_location(), Some(&expected_location));
+    }
+
+    #[test]
+    fn reference_of_unexpected_kind() {
+        let package_set = get_package_set(".acme.common.Money", Type::Enum);
+        let err = package_set.get_resolved_packages().err().unwrap();
+
+        let expected_location = ErrorLocation {
+            file: "person.proto".to_string(),
+            package: "acme.people".to_string(),
+            path: "acme.people.Person.reference".to_string(),
+            position: None,
+        };
+
+        assert!(matches!(
+            &err,
+            PrutoipaBuildError::UnexpectedType { type_name, expected: "an enum", .. }
+                if type_name == ".acme.common.Money"
+        ));
+        assert_eq!(err.get_location(), Some(&expected_location));
+    }
+}
@@ -0,0 +1,232 @@
+use std::fmt::{Display, Formatter};
+
+/// Schema of a component, shaped as the utoipa builders building it. The generators write it as
+/// code and the standalone documents build it at once, so both describe the same schemas.
+#[derive(Debug, Clone)]
+pub enum Schema {
+    Object(ObjectSchema),
+    Array(ArraySchema),
+    OneOf(CompositeSchema),
+    AllOf(CompositeSchema),
+    /// Schema of a descriptor, as its `ToSchema` implementation returns it.
+    Descriptor {
+        package: String,
+        path: String,
+    },
+    /// Reference to the schema of a descriptor, by schema name.
+    Ref(String),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ObjectSchema {
+    pub schema_type: Option<SchemaType>,
+    pub format: Option<KnownFormat>,
+    pub pattern: Option<&'static str>,
+    pub enum_values: Option<Vec<String>>,
+    pub properties: Vec<(String, Schema)>,
+    pub required: Vec<String>,
+    pub additional_properties: Option<AdditionalProperties>,
+    pub nullable: bool,
+    pub default: Option<Literal>,
+    pub deprecated: bool,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArraySchema {
+    pub items: Box<Schema>,
+    pub nullable: bool,
+    pub default: Option<Literal>,
+    pub deprecated: bool,
+    pub description: Option<String>,
+}
+
+/// Schema of `oneOf` and `allOf` compositions, which utoipa can not mark as deprecated.
+#[derive(Debug, Clone, Default)]
+pub struct CompositeSchema {
+    pub items: Vec<Schema>,
+    pub nullable: bool,
+    pub default: Option<Literal>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum AdditionalProperties {
+    FreeForm,
+    Schema(Box<Schema>),
+}
+
+/// Type of a schema, named as the `utoipa::openapi::SchemaType` variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaType {
+    Value,
+    String,
+    Integer,
+    Number,
+    Boolean,
+}
+
+/// Format of a schema, named as the `utoipa::openapi::KnownFormat` variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KnownFormat {
+    Int32,
+    Int64,
+    Float,
+    Double,
+    Byte,
+    DateTime,
+}
+
+/// JSON value of a default value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Int32(i32),
+    Int64(i64),
+    UInt32(u32),
+    UInt64(u64),
+    Double(f64),
+    Bool(bool),
+    String(String),
+}
+
+impl Display for SchemaType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "utoipa::openapi::SchemaType::{self:?}")
+    }
+}
+
+impl Display for KnownFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "utoipa::openapi::KnownFormat::{self:?}")
+    }
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Int32(value) => write!(f, "{value}i32"),
+            Self::Int64(value) => write!(f, "{value}i64"),
+            Self::UInt32(value) => write!(f, "{value}u32"),
+            Self::UInt64(value) => write!(f, "{value}u64"),
+            Self::Double(value) => write!(f, "{value:?}f64"),
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::String(value) => write!(f, "{value:?}"),
+        }
+    }
+}
+
+impl ObjectSchema {
+    pub fn typed(schema_type: SchemaType) -> Self {
+        Self {
+            schema_type: Some(schema_type),
+            ..Default::default()
+        }
+    }
+
+    pub fn property(mut self, name: String, schema: Schema) -> Self {
+        self.properties.push((name, schema));
+        self
+    }
+
+    pub fn required(mut self, name: String) -> Self {
+        self.required.push(name);
+        self
+    }
+}
+
+impl Schema {
+    pub fn array(items: Schema) -> Self {
+        Self::Array(ArraySchema {
+            items: Box::new(items),
+            nullable: false,
+            default: None,
+            deprecated: false,
+            description: None,
+        })
+    }
+
+    pub fn one_of(items: Vec<Schema>) -> Self {
+        Self::OneOf(CompositeSchema {
+            items,
+            ..Default::default()
+        })
+    }
+
+    pub fn all_of(items: Vec<Schema>) -> Self {
+        Self::AllOf(CompositeSchema {
+            items,
+            ..Default::default()
+        })
+    }
+
+    pub fn nullable(mut self, nullable: bool) -> Self {
+        match &mut self {
+            Self::Object(schema) => schema.nullable = nullable,
+            Self::Array(schema) => schema.nullable = nullable,
+            Self::OneOf(schema) | Self::AllOf(schema) => schema.nullable = nullable,
+            Self::Descriptor { .. } | Self::Ref(_) => {}
+        }
+
+        self
+    }
+
+    /// Sets the default value, keeping the current one if there is none.
+    pub fn default(mut self, default: Option<Literal>) -> Self {
+        if default.is_none() {
+            return self;
+        }
+
+        match &mut self {
+            Self::Object(schema) => schema.default = default,
+            Self::Array(schema) => schema.default = default,
+            Self::OneOf(schema) | Self::AllOf(schema) => schema.default = default,
+            Self::Descriptor { .. } | Self::Ref(_) => {}
+        }
+
+        self
+    }
+
+    /// Sets the description, keeping the current one if there is none.
+    pub fn description(mut self, description: Option<String>) -> Self {
+        if description.is_none() {
+            return self;
+        }
+
+        match &mut self {
+            Self::Object(schema) => schema.description = description,
+            Self::Array(schema) => schema.description = description,
+            Self::OneOf(schema) | Self::AllOf(schema) => schema.description = description,
+            Self::Descriptor { .. } | Self::Ref(_) => {}
+        }
+
+        self
+    }
+
+    /// Sets the description and deprecation. utoipa only supports `deprecated` at objects and
+    /// arrays, so `allOf` schemas, like annotated references or messages with flattened one-of
+    /// groups, get an item holding it, while `oneOf` ones are described as deprecated instead.
+    /// Schemas have no extensions either, so deprecated enum values are described, and only the
+    /// standalone documents list them at `x-deprecated-values`.
+    pub fn annotate(mut self, description: Option<String>, deprecated: bool) -> Self {
+        if !deprecated {
+            return self.description(description);
+        }
+
+        match &mut self {
+            Self::Object(schema) => schema.deprecated = true,
+            Self::Array(schema) => schema.deprecated = true,
+            Self::AllOf(schema) => schema.items.push(Self::Object(ObjectSchema {
+                deprecated: true,
+                ..ObjectSchema::typed(SchemaType::Value)
+            })),
+            Self::OneOf(_) | Self::Descriptor { .. } | Self::Ref(_) => {
+                return self.description(Some(match description {
+                    Some(description) => format!("Deprecated.\n\n{description}"),
+                    None => "Deprecated.".to_string(),
+                }));
+            }
+        }
+
+        self.description(description)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::error::PrutoipaBuildError;
+use crate::error::{ErrorLocation, PrutoipaBuildError};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Syntax {
@@ -11,9 +11,10 @@ impl Syntax {
         match syntax {
             None | Some("proto2") => Ok(Syntax::Proto2),
             Some("proto3") => Ok(Syntax::Proto3),
-            Some(s) => Err(PrutoipaBuildError::InvalidData(format!(
-                "Unknown syntax: {s}"
-            ))),
+            Some(s) => Err(PrutoipaBuildError::UnsupportedSyntax {
+                location: ErrorLocation::default().into(),
+                syntax: s.to_string(),
+            }),
         }
     }
 }
@@ -1,36 +1,24 @@
-use std::{env, fs, io::Error, path::PathBuf};
+use std::{env, path::PathBuf};
 
-fn build_protos(
-    descriptors_file_path: &PathBuf,
-    protos_folder: PathBuf,
-    proto_files: Vec<PathBuf>,
-) -> Result<(), Error> {
-    prost_build::Config::new()
-        // Allow proto3 optional
-        .protoc_arg("--experimental_allow_proto3_optional")
-        // Save descriptors to file
-        .file_descriptor_set_path(descriptors_file_path)
-        // Generate prost structs
-        .compile_protos(&proto_files, &[protos_folder])
-}
+use prost::Message;
+use prost_types::FileDescriptorSet;
 
 fn main() {
     // Proto files location
     let protos_folder = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("protos");
     let proto_files = vec![protos_folder.join("person.proto")];
+    let includes = vec![protos_folder];
 
-    // File where proto descriptors will be saved
-    let descriptors_file_path =
-        PathBuf::from(env::var("OUT_DIR").unwrap()).join("proto_descriptor.bin");
-
-    // Build protos
-    build_protos(&descriptors_file_path, protos_folder, proto_files).unwrap();
+    // Build prost structs, compiling the protos in-process
+    let descriptors = prutoipa_build::compile_protos(&proto_files, &includes).unwrap();
+    prost_build::Config::new()
+        .compile_fds(FileDescriptorSet::decode(descriptors.as_slice()).unwrap())
+        .unwrap();
 
     // Build utoipa code
-    let descriptors = fs::read(descriptors_file_path).unwrap();
     prutoipa_build::Builder::new()
         // .generate_enum_values()
-        .register_descriptors_encoded(&descriptors)
+        .compile_protos(&proto_files, &includes)
         .unwrap()
         .build()
         .unwrap();
@@ -0,0 +1,59 @@
+use std::{env, path::PathBuf};
+
+use prutoipa_build::{
+    DocumentFormat, EnumRepresentation, JsonMapping, OneOfStyle, PresencePolicy, PropertyNaming,
+    PropertyPresence,
+};
+
+use prost::Message as _;
+
+fn main() {
+    // Proto files location
+    let protos_folder = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("protos");
+    let proto_files = vec![
+        protos_folder.join("library.proto"),
+        protos_folder.join("recursion.proto"),
+    ];
+    let includes = vec![protos_folder.clone()];
+    println!("cargo:rerun-if-changed={}", protos_folder.display());
+
+    // Build prost structs
+    let descriptors = prutoipa_build::compile_protos(&proto_files, &includes).unwrap();
+    let file_descriptor_set =
+        prost_types::FileDescriptorSet::decode(descriptors.as_slice()).unwrap();
+    prost_build::Config::new()
+        .compile_fds(file_descriptor_set)
+        .unwrap();
+
+    // Build utoipa code, along with the documents it must be equivalent to
+    let mut builder = prutoipa_build::Builder::new();
+    builder
+        .openapi("prutoipa-test", "0.0.0")
+        .field_presence("library.Book.price", PropertyPresence::Optional)
+        .compile_protos(&proto_files, &includes)
+        .unwrap();
+    builder.build().unwrap();
+    builder.build_documents(DocumentFormat::Json).unwrap();
+
+    // Build them again with every option away from its default, at their own folder
+    let options_out_dir = PathBuf::from(env::var("OUT_DIR").unwrap()).join("options");
+    std::fs::create_dir_all(&options_out_dir).unwrap();
+    let mut builder = prutoipa_build::Builder::new();
+    builder
+        .out_dir(options_out_dir)
+        .generate_enum_values()
+        .enum_representation(EnumRepresentation::Either)
+        .oneof_style(OneOfStyle::Nested)
+        .json_mapping(JsonMapping::Numeric)
+        .property_naming(PropertyNaming::ProtoName)
+        .presence_policy(PresencePolicy::WireAccurate)
+        .field_presence("library.Book.id", PropertyPresence::Required)
+        .field_presence("library.Book.title", PropertyPresence::Nullable)
+        .document_implicit_defaults()
+        .markdown_comments()
+        .openapi("prutoipa-test", "0.0.0")
+        .compile_protos(&proto_files, &includes)
+        .unwrap();
+    builder.build().unwrap();
+    builder.build_documents(DocumentFormat::Json).unwrap();
+}
@@ -1,3 +1,26 @@
+pub mod library {
+    include!(concat!(env!("OUT_DIR"), "/library.rs"));
+    include!(concat!(env!("OUT_DIR"), "/library.utoipa.rs"));
+}
+
+/// The same packages, generated with every option away from its default.
+pub mod options {
+    pub mod library {
+        include!(concat!(env!("OUT_DIR"), "/library.rs"));
+        include!(concat!(env!("OUT_DIR"), "/options/library.utoipa.rs"));
+    }
+
+    pub mod recursion {
+        include!(concat!(env!("OUT_DIR"), "/recursion.rs"));
+        include!(concat!(env!("OUT_DIR"), "/options/recursion.utoipa.rs"));
+    }
+}
+
+pub mod recursion {
+    include!(concat!(env!("OUT_DIR"), "/recursion.rs"));
+    include!(concat!(env!("OUT_DIR"), "/recursion.utoipa.rs"));
+}
+
 pub fn add(left: usize, right: usize) -> usize {
     left + right
 }
@@ -5,10 +28,244 @@ pub fn add(left: usize, right: usize) -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::{json, Value};
+    use utoipa::ToSchema;
+
+    /// Returns the OpenAPI document that `build_documents` wrote for a package, without the
+    /// extensions the generated code can not hold.
+    fn get_document(document: &str) -> Value {
+        fn remove_extensions(value: &mut Value) {
+            match value {
+                Value::Object(object) => {
+                    object.remove("x-deprecated-values");
+                    object.values_mut().for_each(remove_extensions);
+                }
+                Value::Array(array) => array.iter_mut().for_each(remove_extensions),
+                _ => {}
+            }
+        }
+
+        let mut document = serde_json::from_str(document).unwrap();
+        remove_extensions(&mut document);
+
+        document
+    }
+
+    fn get_schema<'s, T: ToSchema<'s>>() -> (&'s str, Value) {
+        let (name, schema) = T::schema();
+
+        (name, serde_json::to_value(schema).unwrap())
+    }
 
     #[test]
     fn it_works() {
         let result = add(2, 2);
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn recursive_message() {
+        let (name, schema) = get_schema::<recursion::Tree>();
+        let tree_ref = json!({ "$ref": "#/components/schemas/recursion.Tree" });
+
+        assert_eq!(name, "recursion.Tree");
+        assert_eq!(schema["properties"]["children"]["items"], tree_ref);
+        assert_eq!(
+            schema["properties"]["namedChildren"]["additionalProperties"],
+            tree_ref
+        );
+    }
+
+    #[test]
+    fn message_referencing_recursive_message() {
+        let (_, schema) = get_schema::<recursion::Forest>();
+        let (_, tree_schema) = get_schema::<recursion::Tree>();
+
+        // Only the references closing a cycle are not inlined
+        assert_eq!(schema["properties"]["trees"]["items"], tree_schema);
+    }
+
+    #[test]
+    fn mutually_recursive_messages() {
+        let (_, schema) = get_schema::<recursion::Expression>();
+        let (_, sum_schema) = get_schema::<recursion::Sum>();
+
+        assert_eq!(
+            schema
+                .to_string()
+                .matches("#/components/schemas/recursion.Sum")
+                .count(),
+            1
+        );
+        assert_eq!(
+            sum_schema["properties"]["operands"]["items"],
+            json!({ "$ref": "#/components/schemas/recursion.Expression" })
+        );
+    }
+
+    #[test]
+    fn recursive_components() {
+        let components = recursion::components();
+        let openapi = recursion::openapi();
+
+        assert_eq!(
+            components.schemas.keys().collect::<Vec<&String>>(),
+            vec![
+                "recursion.Expression",
+                "recursion.Forest",
+                "recursion.Sum",
+                "recursion.Tree"
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(openapi.components).unwrap(),
+            serde_json::to_value(Some(components)).unwrap()
+        );
+    }
+
+    #[test]
+    fn library_document() {
+        assert_eq!(
+            serde_json::to_value(library::openapi()).unwrap(),
+            get_document(include_str!(concat!(
+                env!("OUT_DIR"),
+                "/library.openapi.json"
+            )))
+        );
+    }
+
+    #[test]
+    fn recursion_document() {
+        assert_eq!(
+            serde_json::to_value(recursion::openapi()).unwrap(),
+            get_document(include_str!(concat!(
+                env!("OUT_DIR"),
+                "/recursion.openapi.json"
+            )))
+        );
+    }
+
+    #[test]
+    fn options_library_document() {
+        assert_eq!(
+            serde_json::to_value(options::library::openapi()).unwrap(),
+            get_document(include_str!(concat!(
+                env!("OUT_DIR"),
+                "/options/library.openapi.json"
+            )))
+        );
+    }
+
+    #[test]
+    fn options_recursion_document() {
+        assert_eq!(
+            serde_json::to_value(options::recursion::openapi()).unwrap(),
+            get_document(include_str!(concat!(
+                env!("OUT_DIR"),
+                "/options/recursion.openapi.json"
+            )))
+        );
+    }
+
+    #[test]
+    fn flattened_query_parameters() {
+        let openapi = serde_json::to_value(library::openapi()).unwrap();
+        let parameters = openapi["paths"]["/v1/books"]["get"]["parameters"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|parameter| parameter["name"].as_str().unwrap())
+            .collect::<Vec<&str>>();
+
+        assert_eq!(
+            parameters,
+            vec![
+                "pageSize",
+                "pageToken",
+                "genre",
+                "authors",
+                "filter.title",
+                "filter.publishedAfter"
+            ]
+        );
+    }
+
+    #[test]
+    fn deprecated_wrapped_schemas() {
+        let (_, book_schema) = get_schema::<library::Book>();
+        let (_, loan_schema) = get_schema::<library::Loan>();
+        let deprecated = json!({ "deprecated": true });
+
+        // Books have a flattened one-of group, so their properties are at the first item
+        for property in ["oldGenre", "firstEdition"] {
+            let all_of = book_schema["allOf"][0]["properties"][property]["allOf"]
+                .as_array()
+                .unwrap();
+
+            assert_eq!(all_of.len(), 2);
+            assert_eq!(all_of[1], deprecated);
+        }
+        assert!(loan_schema["allOf"]
+            .as_array()
+            .unwrap()
+            .contains(&deprecated));
+    }
+
+    #[test]
+    fn deprecated_enum_values() {
+        let document: Value = serde_json::from_str(include_str!(concat!(
+            env!("OUT_DIR"),
+            "/library.openapi.json"
+        )))
+        .unwrap();
+        let schemas = &document["components"]["schemas"];
+
+        assert_eq!(
+            schemas["library.Genre"]["x-deprecated-values"],
+            json!(["GENRE_POETRY"])
+        );
+        assert_eq!(
+            schemas["library.ListBooksRequest"]["properties"]["genre"]["x-deprecated-values"],
+            json!(["GENRE_POETRY"])
+        );
+    }
+
+    #[test]
+    fn compiled_comments() {
+        let (_, schema) = get_schema::<library::Book>();
+        let openapi = serde_json::to_value(library::openapi()).unwrap();
+        let properties = &schema["allOf"][0]["properties"];
+
+        assert_eq!(schema["description"], json!("A book of the library."));
+        assert_eq!(properties["id"]["description"], json!("Its id."));
+        assert_eq!(
+            openapi["paths"]["/v1/books/{id}"]["get"]["summary"],
+            json!("Gets a book.")
+        );
+    }
+
+    #[test]
+    fn strict_presence_with_overrides() {
+        let (_, schema) = get_schema::<library::Book>();
+        let properties = &schema["allOf"][0];
+
+        // Price is overridden as optional
+        assert_eq!(
+            properties["required"],
+            json!(["id", "title", "cover", "available", "isbn"])
+        );
+        assert_eq!(properties["properties"]["subtitle"].get("nullable"), None);
+    }
+
+    #[test]
+    fn wire_accurate_presence_with_overrides() {
+        let (_, schema) = get_schema::<options::library::Book>();
+        let properties = &schema["properties"];
+
+        // Id is overridden as required and title as nullable
+        assert_eq!(schema["required"], json!(["id"]));
+        assert_eq!(properties["subtitle"]["nullable"], json!(true));
+        assert_eq!(properties["title"]["nullable"], json!(true));
+        assert_eq!(properties["price"].get("nullable"), None);
+    }
 }